@@ -0,0 +1,240 @@
+use std::fmt::Error as FormatterError;
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+///
+/// Whether the authorization server should prompt the end-user for reauthentication and
+/// consent, as defined in
+/// [Section 3.1.2.1 of OpenID Connect Core 1.0](https://openid.net/specs/openid-connect-core-1_0.html#AuthRequest).
+///
+/// [`Prompt::None`] must not be combined with any other value (see
+/// [`AuthorizationRequest::try_url`](crate::AuthorizationRequest::try_url)).
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Prompt {
+    ///
+    /// The authorization server must not display any authentication or consent user interface
+    /// pages, and instead returns an error if the end-user is not already authenticated or has
+    /// not pre-configured consent.
+    ///
+    None,
+    ///
+    /// The authorization server should prompt the end-user for reauthentication.
+    ///
+    Login,
+    ///
+    /// The authorization server should prompt the end-user for consent before returning
+    /// information to the client.
+    ///
+    Consent,
+    ///
+    /// The authorization server should prompt the end-user to select a user account.
+    ///
+    SelectAccount,
+    ///
+    /// The authorization server should display the account creation user interface
+    /// ([OpenID Connect Initiating User Registration](https://openid.net/specs/openid-connect-prompt-create-1_0.html)).
+    ///
+    Create,
+    ///
+    /// An extension not defined by any of the supported specifications.
+    ///
+    Extension(String),
+}
+impl AsRef<str> for Prompt {
+    fn as_ref(&self) -> &str {
+        match self {
+            Prompt::None => "none",
+            Prompt::Login => "login",
+            Prompt::Consent => "consent",
+            Prompt::SelectAccount => "select_account",
+            Prompt::Create => "create",
+            Prompt::Extension(extension) => extension,
+        }
+    }
+}
+impl Display for Prompt {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+///
+/// Mechanism for returning parameters from the authorization endpoint, as defined in
+/// [OAuth 2.0 Multiple Response Type Encoding Practices](https://openid.net/specs/oauth-v2-multiple-response-types-1_0.html#ResponseModes),
+/// [OAuth 2.0 Form Post Response Mode](https://openid.net/specs/oauth-v2-form-post-response-mode-1_0.html)
+/// and [JWT Secured Authorization Response Mode (JARM)](https://openid.net/specs/oauth-v2-jarm.html).
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ResponseMode {
+    ///
+    /// Parameters are encoded in the query string of the redirect URI.
+    ///
+    Query,
+    ///
+    /// Parameters are encoded in the fragment of the redirect URI.
+    ///
+    Fragment,
+    ///
+    /// Parameters are encoded as HTML form values that are auto-submitted to the redirect URI
+    /// using HTTP POST.
+    ///
+    FormPost,
+    ///
+    /// Parameters are returned as a signed JWT using the default mode for the response type.
+    ///
+    Jwt,
+    ///
+    /// Parameters are returned as a signed JWT in the query string of the redirect URI.
+    ///
+    QueryJwt,
+    ///
+    /// Parameters are returned as a signed JWT in the fragment of the redirect URI.
+    ///
+    FragmentJwt,
+    ///
+    /// Parameters are returned as a signed JWT using HTTP POST.
+    ///
+    FormPostJwt,
+    ///
+    /// An extension not defined by any of the supported specifications.
+    ///
+    Extension(String),
+}
+impl AsRef<str> for ResponseMode {
+    fn as_ref(&self) -> &str {
+        match self {
+            ResponseMode::Query => "query",
+            ResponseMode::Fragment => "fragment",
+            ResponseMode::FormPost => "form_post",
+            ResponseMode::Jwt => "jwt",
+            ResponseMode::QueryJwt => "query.jwt",
+            ResponseMode::FragmentJwt => "fragment.jwt",
+            ResponseMode::FormPostJwt => "form_post.jwt",
+            ResponseMode::Extension(extension) => extension,
+        }
+    }
+}
+impl Display for ResponseMode {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+///
+/// How the authorization server should display the authentication and consent user interface
+/// pages, as defined in
+/// [Section 3.1.2.1 of OpenID Connect Core 1.0](https://openid.net/specs/openid-connect-core-1_0.html#AuthRequest).
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AuthDisplay {
+    ///
+    /// Full user agent page view (the default).
+    ///
+    Page,
+    ///
+    /// Popup user agent window.
+    ///
+    Popup,
+    ///
+    /// User interface optimized for touch devices.
+    ///
+    Touch,
+    ///
+    /// User interface optimized for "feature phones".
+    ///
+    Wap,
+}
+impl AsRef<str> for AuthDisplay {
+    fn as_ref(&self) -> &str {
+        match self {
+            AuthDisplay::Page => "page",
+            AuthDisplay::Popup => "popup",
+            AuthDisplay::Touch => "touch",
+            AuthDisplay::Wap => "wap",
+        }
+    }
+}
+impl Display for AuthDisplay {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+///
+/// Error encountered while constructing a [`ClaimsRequest`].
+///
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ClaimsRequestError {
+    ///
+    /// The claims request is not a JSON object.
+    ///
+    #[error("Claims request must be a JSON object")]
+    NotAnObject,
+    ///
+    /// The member with the included name is neither a JSON object nor `null`.
+    ///
+    #[error("Claims request member `{}` must be a JSON object or null", _0)]
+    InvalidMember(String),
+}
+
+///
+/// Request for specific claims to be returned from the UserInfo endpoint and/or in the ID
+/// token, as defined in
+/// [Section 5.5 of OpenID Connect Core 1.0](https://openid.net/specs/openid-connect-core-1_0.html#ClaimsParameter).
+///
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(try_from = "Value", into = "Value")]
+pub struct ClaimsRequest(Map<String, Value>);
+impl ClaimsRequest {
+    ///
+    /// Creates a claims request from a JSON object whose members (e.g., `userinfo` and
+    /// `id_token`) are each a JSON object or `null`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use oauth2::authorization::ClaimsRequest;
+    ///
+    /// let claims = ClaimsRequest::new(serde_json::json!({
+    ///     "id_token": { "email": { "essential": true } },
+    ///     "userinfo": { "picture": null },
+    /// }))
+    /// .unwrap();
+    /// ```
+    ///
+    pub fn new(claims: Value) -> Result<Self, ClaimsRequestError> {
+        let claims = match claims {
+            Value::Object(claims) => claims,
+            _ => return Err(ClaimsRequestError::NotAnObject),
+        };
+        if let Some((name, _)) = claims
+            .iter()
+            .find(|(_, member)| !member.is_object() && !member.is_null())
+        {
+            return Err(ClaimsRequestError::InvalidMember(name.clone()));
+        }
+        Ok(ClaimsRequest(claims))
+    }
+}
+impl std::convert::TryFrom<Value> for ClaimsRequest {
+    type Error = ClaimsRequestError;
+
+    fn try_from(claims: Value) -> Result<Self, Self::Error> {
+        ClaimsRequest::new(claims)
+    }
+}
+impl From<ClaimsRequest> for Value {
+    fn from(claims: ClaimsRequest) -> Self {
+        Value::Object(claims.0)
+    }
+}
+impl Display for ClaimsRequest {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        let claims = serde_json::to_string(&self.0).map_err(|_| FormatterError)?;
+        f.write_str(&claims)
+    }
+}
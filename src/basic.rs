@@ -2,11 +2,13 @@ use std::fmt::Error as FormatterError;
 use std::fmt::{Debug, Display, Formatter};
 
 use super::{
-    Client, EmptyExtraTokenFields, ErrorResponseType, RequestTokenError, StandardErrorResponse,
-    StandardTokenResponse, TokenType,
+    Client, ClientBuilder, EmptyExtraTokenFields, ErrorResponseType, RequestTokenError,
+    StandardErrorResponse, StandardTokenResponse, TokenType,
 };
 use crate::{
     revocation::{RevocationErrorResponseType, StandardRevocableToken},
+    strict::StrictClient,
+    typestate::{EndpointNotSet, TypedClient},
     StandardTokenIntrospectionResponse,
 };
 
@@ -22,6 +24,53 @@ pub type BasicClient = Client<
     BasicRevocationErrorResponse,
 >;
 
+///
+/// Basic OAuth2 client specialization of [`TypedClient`], which tracks configured endpoints at
+/// compile time.
+///
+pub type BasicTypedClient<
+    HT = EndpointNotSet,
+    HI = EndpointNotSet,
+    HR = EndpointNotSet,
+    HD = EndpointNotSet,
+> = TypedClient<
+    BasicErrorResponse,
+    BasicTokenResponse,
+    BasicTokenType,
+    BasicTokenIntrospectionResponse,
+    StandardRevocableToken,
+    BasicRevocationErrorResponse,
+    HT,
+    HI,
+    HR,
+    HD,
+>;
+
+///
+/// Basic OAuth2 client specialization of [`StrictClient`], which enforces the requirements of
+/// OAuth 2.1.
+///
+pub type BasicStrictClient = StrictClient<
+    BasicErrorResponse,
+    BasicTokenResponse,
+    BasicTokenType,
+    BasicTokenIntrospectionResponse,
+    StandardRevocableToken,
+    BasicRevocationErrorResponse,
+>;
+
+///
+/// Builder for a [`BasicClient`].
+///
+pub type BasicClientBuilder = ClientBuilder<
+    BasicErrorResponse,
+    BasicTokenResponse,
+    BasicTokenType,
+    BasicTokenIntrospectionResponse,
+    StandardRevocableToken,
+    BasicRevocationErrorResponse,
+>;
+
 ///
 /// Basic OAuth2 authorization token types.
 ///
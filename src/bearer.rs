@@ -0,0 +1,849 @@
+use std::fmt::Error as FormatterError;
+use std::fmt::{Debug, Display, Formatter};
+use std::time::Duration;
+
+use http::header::{
+    HeaderMap, HeaderValue, AUTHORIZATION, CACHE_CONTROL, CONTENT_TYPE, WWW_AUTHENTICATE,
+};
+use http::method::Method;
+use http::uri::{PathAndQuery, Uri};
+use url::form_urlencoded;
+
+use crate::basic::BasicTokenType;
+use crate::{AccessToken, ErrorResponseType, HttpRequest, Scope};
+
+const ACCESS_TOKEN_PARAM: &str = "access_token";
+const FORM_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+
+///
+/// Error encountered while applying an access token to a request.
+///
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// The token type has no supported authorization scheme (e.g., MAC tokens, which require
+    /// signing each request).
+    #[error("Unsupported token type `{}`", _0)]
+    UnsupportedTokenType(String),
+    /// Form-encoded body parameters require a request that has a body (i.e., not `GET`) and a
+    /// `application/x-www-form-urlencoded` content type.
+    #[error("Request cannot carry a form-encoded access token")]
+    InvalidFormRequest,
+    /// The access token contains characters that are not allowed in an HTTP header.
+    #[error("Access token is not a valid header value")]
+    InvalidHeaderValue(#[source] http::header::InvalidHeaderValue),
+    /// The request URI could not be updated with the access token.
+    #[error("Failed to add access token to request URI")]
+    InvalidUri(#[source] http::Error),
+}
+
+///
+/// Method used to send a bearer token to a protected resource, as defined in
+/// [Section 2 of RFC 6750](https://tools.ietf.org/html/rfc6750#section-2).
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BearerTokenMethod {
+    ///
+    /// `Authorization: Bearer` request header
+    /// ([Section 2.1](https://tools.ietf.org/html/rfc6750#section-2.1)). This is the
+    /// recommended method and is supported by all resource servers.
+    ///
+    AuthorizationHeader,
+    ///
+    /// `access_token` parameter in an `application/x-www-form-urlencoded` request body
+    /// ([Section 2.2](https://tools.ietf.org/html/rfc6750#section-2.2)).
+    ///
+    FormBody,
+    ///
+    /// `access_token` URI query parameter
+    /// ([Section 2.3](https://tools.ietf.org/html/rfc6750#section-2.3)). Since URIs are
+    /// frequently logged, this method should only be used if neither of the others is possible.
+    ///
+    Query,
+}
+
+///
+/// Request body to which form-encoded parameters can be appended.
+///
+pub trait FormBody {
+    ///
+    /// Appends the form-encoded parameter `name=value` to the body.
+    ///
+    fn append_form_param(&mut self, name: &str, value: &str);
+}
+impl FormBody for Vec<u8> {
+    fn append_form_param(&mut self, name: &str, value: &str) {
+        if !self.is_empty() {
+            self.push(b'&');
+        }
+        self.extend_from_slice(encode_param(name, value).as_bytes());
+    }
+}
+impl FormBody for String {
+    fn append_form_param(&mut self, name: &str, value: &str) {
+        if !self.is_empty() {
+            self.push('&');
+        }
+        self.push_str(&encode_param(name, value));
+    }
+}
+
+fn encode_param(name: &str, value: &str) -> String {
+    form_urlencoded::Serializer::new(String::new())
+        .append_pair(name, value)
+        .finish()
+}
+
+///
+/// Sends a bearer token ([RFC 6750](https://tools.ietf.org/html/rfc6750)) with `request` using
+/// the given `method`.
+///
+/// When using [`BearerTokenMethod::FormBody`], the request's `Content-Type` header is set to
+/// `application/x-www-form-urlencoded` if absent. When using [`BearerTokenMethod::Query`], a
+/// `Cache-Control: no-store` header is added as recommended by
+/// [Section 2.3](https://tools.ietf.org/html/rfc6750#section-2.3).
+///
+/// # Example
+///
+/// ```rust
+/// # fn err_wrapper() -> Result<(), anyhow::Error> {
+/// use oauth2::bearer::{authorize_request, BearerTokenMethod};
+/// use oauth2::http::Request;
+/// use oauth2::AccessToken;
+///
+/// let token = AccessToken::new("mF_9.B5f-4.1JqM".to_string());
+/// let mut request = Request::get("https://api.example.com/resource").body(Vec::new())?;
+/// authorize_request(&mut request, &token, BearerTokenMethod::AuthorizationHeader)?;
+/// assert_eq!(request.headers()["authorization"], "Bearer mF_9.B5f-4.1JqM");
+/// # Ok(())
+/// # }
+/// ```
+///
+pub fn authorize_request<B>(
+    request: &mut http::Request<B>,
+    token: &AccessToken,
+    method: BearerTokenMethod,
+) -> Result<(), Error>
+where
+    B: FormBody,
+{
+    match method {
+        BearerTokenMethod::AuthorizationHeader => {
+            authorize_request_for_token_type(request, token, &BasicTokenType::Bearer)
+        }
+        BearerTokenMethod::FormBody => {
+            let request_method = request.method().clone();
+            prepare_form_request(&request_method, request.headers_mut())?;
+            request
+                .body_mut()
+                .append_form_param(ACCESS_TOKEN_PARAM, token.secret());
+            Ok(())
+        }
+        BearerTokenMethod::Query => {
+            let uri = request.uri();
+            let path_and_query = match uri.query() {
+                Some(query) if !query.is_empty() => format!(
+                    "{}?{}&{}",
+                    uri.path(),
+                    query,
+                    encode_param(ACCESS_TOKEN_PARAM, token.secret())
+                ),
+                _ => format!(
+                    "{}?{}",
+                    uri.path(),
+                    encode_param(ACCESS_TOKEN_PARAM, token.secret())
+                ),
+            };
+            let mut parts = uri.clone().into_parts();
+            parts.path_and_query = Some(
+                PathAndQuery::from_maybe_shared(path_and_query)
+                    .map_err(|err| Error::InvalidUri(err.into()))?,
+            );
+            *request.uri_mut() =
+                Uri::from_parts(parts).map_err(|err| Error::InvalidUri(err.into()))?;
+            set_no_store(request.headers_mut());
+            Ok(())
+        }
+    }
+}
+
+///
+/// Sends a bearer token ([RFC 6750](https://tools.ietf.org/html/rfc6750)) with `request` using
+/// the given `method`.
+///
+/// See [`authorize_request`] for details.
+///
+pub fn authorize_http_request(
+    request: &mut HttpRequest,
+    token: &AccessToken,
+    method: BearerTokenMethod,
+) -> Result<(), Error> {
+    match method {
+        BearerTokenMethod::AuthorizationHeader => {
+            authorize_http_request_for_token_type(request, token, &BasicTokenType::Bearer)
+        }
+        BearerTokenMethod::FormBody => {
+            prepare_form_request(&request.method, &mut request.headers)?;
+            request
+                .body
+                .append_form_param(ACCESS_TOKEN_PARAM, token.secret());
+            Ok(())
+        }
+        BearerTokenMethod::Query => {
+            request
+                .url
+                .query_pairs_mut()
+                .append_pair(ACCESS_TOKEN_PARAM, token.secret());
+            set_no_store(&mut request.headers);
+            Ok(())
+        }
+    }
+}
+
+///
+/// Sets the `Authorization` header of `request` using the scheme that matches `token_type`.
+///
+/// See [`authorization_header_value`] for the supported token types.
+///
+pub fn authorize_request_for_token_type<B>(
+    request: &mut http::Request<B>,
+    token: &AccessToken,
+    token_type: &BasicTokenType,
+) -> Result<(), Error> {
+    let value = authorization_header_value(token, token_type)?;
+    request.headers_mut().insert(AUTHORIZATION, value);
+    Ok(())
+}
+
+///
+/// Sets the `Authorization` header of `request` using the scheme that matches `token_type`.
+///
+/// See [`authorization_header_value`] for the supported token types.
+///
+pub fn authorize_http_request_for_token_type(
+    request: &mut HttpRequest,
+    token: &AccessToken,
+    token_type: &BasicTokenType,
+) -> Result<(), Error> {
+    let value = authorization_header_value(token, token_type)?;
+    request.headers.insert(AUTHORIZATION, value);
+    Ok(())
+}
+
+fn prepare_form_request(method: &Method, headers: &mut http::HeaderMap) -> Result<(), Error> {
+    // See https://tools.ietf.org/html/rfc6750#section-2.2.
+    if method == Method::GET || method == Method::HEAD {
+        return Err(Error::InvalidFormRequest);
+    }
+    match headers.get(CONTENT_TYPE) {
+        Some(content_type) => {
+            let is_form = content_type
+                .to_str()
+                .map(|content_type| {
+                    content_type
+                        .split(';')
+                        .next()
+                        .unwrap_or("")
+                        .trim()
+                        .eq_ignore_ascii_case(FORM_CONTENT_TYPE)
+                })
+                .unwrap_or(false);
+            if !is_form {
+                return Err(Error::InvalidFormRequest);
+            }
+        }
+        None => {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static(FORM_CONTENT_TYPE));
+        }
+    }
+    Ok(())
+}
+
+fn set_no_store(headers: &mut http::HeaderMap) {
+    headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+}
+
+///
+/// Returns the `Authorization` header value for sending `token` using the scheme that matches
+/// `token_type`.
+///
+/// Bearer tokens use the `Bearer` scheme ([RFC 6750](https://tools.ietf.org/html/rfc6750)) and
+/// DPoP-bound tokens use the `DPoP` scheme
+/// ([RFC 9449](https://www.rfc-editor.org/rfc/rfc9449#section-7.1)). Token types are compared
+/// case-insensitively. Note that DPoP-bound tokens must also be accompanied by a `DPoP` proof
+/// header, which the caller is responsible for adding. MAC tokens and other token types are not
+/// supported and result in [`Error::UnsupportedTokenType`].
+///
+pub fn authorization_header_value(
+    token: &AccessToken,
+    token_type: &BasicTokenType,
+) -> Result<HeaderValue, Error> {
+    let scheme = authorization_scheme(token_type)?;
+    let mut value = HeaderValue::from_str(&format!("{} {}", scheme, token.secret()))
+        .map_err(Error::InvalidHeaderValue)?;
+    value.set_sensitive(true);
+    Ok(value)
+}
+
+fn authorization_scheme(token_type: &BasicTokenType) -> Result<&'static str, Error> {
+    match token_type {
+        BasicTokenType::Bearer => Ok("Bearer"),
+        BasicTokenType::Extension(ext) if ext.eq_ignore_ascii_case("bearer") => Ok("Bearer"),
+        BasicTokenType::Extension(ext) if ext.eq_ignore_ascii_case("dpop") => Ok("DPoP"),
+        other => Err(Error::UnsupportedTokenType(other.as_ref().to_string())),
+    }
+}
+
+///
+/// Error codes returned by protected resources in `WWW-Authenticate` challenges.
+///
+/// These error codes are defined in
+/// [Section 3.1 of RFC 6750](https://tools.ietf.org/html/rfc6750#section-3.1) and
+/// [Section 3 of RFC 9470](https://www.rfc-editor.org/rfc/rfc9470#section-3).
+///
+#[derive(Clone, PartialEq)]
+pub enum BearerErrorResponseType {
+    ///
+    /// The request is missing a required parameter, includes an unsupported parameter or
+    /// parameter value, repeats the same parameter, uses more than one method for including an
+    /// access token, or is otherwise malformed.
+    ///
+    InvalidRequest,
+    ///
+    /// The access token provided is expired, revoked, malformed, or invalid for other reasons.
+    /// The client may request a new access token and retry the request.
+    ///
+    InvalidToken,
+    ///
+    /// The request requires higher privileges than provided by the access token. The client
+    /// may request a new access token with the scopes indicated by the challenge.
+    ///
+    InsufficientScope,
+    ///
+    /// The authentication event associated with the access token does not meet the
+    /// requirements of the protected resource. The client may obtain a new access token by
+    /// re-authenticating the user with the `acr_values` and/or `max_age` indicated by the
+    /// challenge.
+    ///
+    InsufficientUserAuthentication,
+    ///
+    /// An extension not defined by RFC 6750 or RFC 9470.
+    ///
+    Extension(String),
+}
+impl BearerErrorResponseType {
+    fn from_str(s: &str) -> Self {
+        match s {
+            "invalid_request" => BearerErrorResponseType::InvalidRequest,
+            "invalid_token" => BearerErrorResponseType::InvalidToken,
+            "insufficient_scope" => BearerErrorResponseType::InsufficientScope,
+            "insufficient_user_authentication" => {
+                BearerErrorResponseType::InsufficientUserAuthentication
+            }
+            ext => BearerErrorResponseType::Extension(ext.to_string()),
+        }
+    }
+}
+impl AsRef<str> for BearerErrorResponseType {
+    fn as_ref(&self) -> &str {
+        match self {
+            BearerErrorResponseType::InvalidRequest => "invalid_request",
+            BearerErrorResponseType::InvalidToken => "invalid_token",
+            BearerErrorResponseType::InsufficientScope => "insufficient_scope",
+            BearerErrorResponseType::InsufficientUserAuthentication => {
+                "insufficient_user_authentication"
+            }
+            BearerErrorResponseType::Extension(ext) => ext.as_str(),
+        }
+    }
+}
+impl<'de> serde::Deserialize<'de> for BearerErrorResponseType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        let variant_str = String::deserialize(deserializer)?;
+        Ok(Self::from_str(&variant_str))
+    }
+}
+impl serde::ser::Serialize for BearerErrorResponseType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        serializer.serialize_str(self.as_ref())
+    }
+}
+impl ErrorResponseType for BearerErrorResponseType {}
+impl Debug for BearerErrorResponseType {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        Display::fmt(self, f)
+    }
+}
+impl Display for BearerErrorResponseType {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+///
+/// Error encountered while parsing a `WWW-Authenticate` header.
+///
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ChallengeParseError {
+    /// The header value contains characters other than visible ASCII.
+    #[error("WWW-Authenticate header contains non-ASCII characters")]
+    NonAscii,
+    /// The header value does not conform to the syntax defined in
+    /// [Section 4.1 of RFC 7235](https://tools.ietf.org/html/rfc7235#section-4.1).
+    #[error("Malformed WWW-Authenticate header at byte {}: {}", _0, _1)]
+    Syntax(usize, &'static str),
+}
+
+///
+/// An authentication challenge returned by a protected resource in a `WWW-Authenticate`
+/// response header ([RFC 7235](https://tools.ietf.org/html/rfc7235#section-4.1)).
+///
+/// Bearer challenges ([RFC 6750](https://tools.ietf.org/html/rfc6750#section-3)) indicate why a
+/// request was rejected, which lets clients decide whether to refresh the access token
+/// ([`BearerErrorResponseType::InvalidToken`]), request additional scopes
+/// ([`BearerErrorResponseType::InsufficientScope`]) or re-authenticate the user
+/// ([`BearerErrorResponseType::InsufficientUserAuthentication`]).
+///
+/// # Example
+///
+/// ```rust
+/// # fn err_wrapper() -> Result<(), anyhow::Error> {
+/// use oauth2::bearer::{BearerErrorResponseType, Challenge};
+///
+/// let challenges = Challenge::parse(
+///     "Bearer realm=\"example\", error=\"insufficient_scope\", scope=\"read write\"",
+/// )?;
+/// assert_eq!(
+///     Some(&BearerErrorResponseType::InsufficientScope),
+///     challenges[0].error()
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Challenge {
+    scheme: String,
+    token68: Option<String>,
+    error: Option<BearerErrorResponseType>,
+    params: Vec<(String, String)>,
+}
+impl Challenge {
+    ///
+    /// Creates a challenge for the given authentication scheme with no parameters.
+    ///
+    pub fn new(scheme: &str) -> Self {
+        Challenge {
+            scheme: scheme.to_string(),
+            token68: None,
+            error: None,
+            params: Vec::new(),
+        }
+    }
+
+    ///
+    /// Creates a `Bearer` challenge with no parameters.
+    ///
+    pub fn bearer() -> Self {
+        Self::new("Bearer")
+    }
+
+    ///
+    /// Sets the parameter `name` to `value`, replacing any existing value.
+    ///
+    pub fn set_param(mut self, name: &str, value: &str) -> Self {
+        let name = name.to_ascii_lowercase();
+        if name == "error" {
+            self.error = Some(BearerErrorResponseType::from_str(value));
+        }
+        match self
+            .params
+            .iter_mut()
+            .find(|(param_name, _)| *param_name == name)
+        {
+            Some((_, param_value)) => *param_value = value.to_string(),
+            None => self.params.push((name, value.to_string())),
+        }
+        self
+    }
+
+    ///
+    /// Sets the protection space (`realm`) of the protected resource.
+    ///
+    pub fn set_realm(self, realm: &str) -> Self {
+        self.set_param("realm", realm)
+    }
+
+    ///
+    /// Sets the error code indicating why the request was rejected.
+    ///
+    pub fn set_error(self, error: BearerErrorResponseType) -> Self {
+        self.set_param("error", error.as_ref())
+    }
+
+    ///
+    /// Sets the human-readable explanation of the error.
+    ///
+    pub fn set_error_description(self, error_description: &str) -> Self {
+        self.set_param("error_description", error_description)
+    }
+
+    ///
+    /// Sets the URI of a human-readable web page with information about the error.
+    ///
+    pub fn set_error_uri(self, error_uri: &str) -> Self {
+        self.set_param("error_uri", error_uri)
+    }
+
+    ///
+    /// Sets the scopes required to access the protected resource.
+    ///
+    pub fn set_scopes(self, scopes: &[Scope]) -> Self {
+        let scopes = scopes
+            .iter()
+            .map(|scope| scope.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.set_param("scope", &scopes)
+    }
+
+    ///
+    /// Returns the challenge as a `WWW-Authenticate` header value.
+    ///
+    /// Fails if any parameter value contains control characters.
+    ///
+    pub fn to_header_value(&self) -> Result<HeaderValue, http::header::InvalidHeaderValue> {
+        HeaderValue::from_str(&self.to_string())
+    }
+
+    ///
+    /// Parses all challenges contained in a single `WWW-Authenticate` header value.
+    ///
+    pub fn parse(header_value: &str) -> Result<Vec<Self>, ChallengeParseError> {
+        ChallengeParser::new(header_value)?.parse()
+    }
+
+    ///
+    /// Parses the challenges contained in all `WWW-Authenticate` headers in `headers`.
+    ///
+    pub fn from_headers(headers: &HeaderMap) -> Result<Vec<Self>, ChallengeParseError> {
+        let mut challenges = Vec::new();
+        for value in headers.get_all(WWW_AUTHENTICATE) {
+            let value = value.to_str().map_err(|_| ChallengeParseError::NonAscii)?;
+            challenges.extend(Self::parse(value)?);
+        }
+        Ok(challenges)
+    }
+
+    ///
+    /// Returns the first `Bearer` challenge in `headers`.
+    ///
+    /// `WWW-Authenticate` headers that fail to parse are skipped.
+    ///
+    pub fn bearer_from_headers(headers: &HeaderMap) -> Option<Self> {
+        headers
+            .get_all(WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| Self::parse(value).ok())
+            .flatten()
+            .find(Self::is_bearer)
+    }
+
+    ///
+    /// Authentication scheme (e.g., `Bearer`).
+    ///
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    ///
+    /// Returns `true` if this is a `Bearer` challenge. Schemes are compared case-insensitively.
+    ///
+    pub fn is_bearer(&self) -> bool {
+        self.scheme.eq_ignore_ascii_case("bearer")
+    }
+
+    ///
+    /// The `token68` credentials included with the challenge instead of parameters, if any.
+    ///
+    pub fn token68(&self) -> Option<&str> {
+        self.token68.as_deref()
+    }
+
+    ///
+    /// All parameters of the challenge in the order they appeared. Parameter names are
+    /// lowercase.
+    ///
+    pub fn params(&self) -> &[(String, String)] {
+        &self.params
+    }
+
+    ///
+    /// Returns the value of the parameter `name` (compared case-insensitively).
+    ///
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param_name, _)| param_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    ///
+    /// The protection space (`realm`) of the protected resource.
+    ///
+    pub fn realm(&self) -> Option<&str> {
+        self.param("realm")
+    }
+
+    ///
+    /// The error code indicating why the request was rejected.
+    ///
+    pub fn error(&self) -> Option<&BearerErrorResponseType> {
+        self.error.as_ref()
+    }
+
+    ///
+    /// Human-readable explanation of the error.
+    ///
+    pub fn error_description(&self) -> Option<&str> {
+        self.param("error_description")
+    }
+
+    ///
+    /// URI of a human-readable web page with information about the error.
+    ///
+    pub fn error_uri(&self) -> Option<&str> {
+        self.param("error_uri")
+    }
+
+    ///
+    /// Scopes required to access the protected resource.
+    ///
+    pub fn scopes(&self) -> Option<Vec<Scope>> {
+        self.param("scope").map(split_space_delimited(Scope::new))
+    }
+
+    ///
+    /// Authentication Context Class Reference values that the user must be authenticated with
+    /// ([RFC 9470](https://www.rfc-editor.org/rfc/rfc9470#section-3)).
+    ///
+    pub fn acr_values(&self) -> Option<Vec<String>> {
+        self.param("acr_values").map(split_space_delimited(|v| v))
+    }
+
+    ///
+    /// Maximum time elapsed since the user last authenticated
+    /// ([RFC 9470](https://www.rfc-editor.org/rfc/rfc9470#section-3)). Returns `None` if absent
+    /// or not a valid number of seconds.
+    ///
+    pub fn max_age(&self) -> Option<Duration> {
+        self.param("max_age")
+            .and_then(|max_age| max_age.parse().ok())
+            .map(Duration::from_secs)
+    }
+}
+
+impl Display for Challenge {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        write!(f, "{}", self.scheme)?;
+        if let Some(token68) = self.token68.as_ref() {
+            write!(f, " {}", token68)?;
+        }
+        for (i, (name, value)) in self.params.iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            write!(f, "{}{}=\"", separator, name)?;
+            for c in value.chars() {
+                if c == '"' || c == '\\' {
+                    write!(f, "\\")?;
+                }
+                write!(f, "{}", c)?;
+            }
+            write!(f, "\"")?;
+        }
+        Ok(())
+    }
+}
+
+fn split_space_delimited<T, F>(f: F) -> impl Fn(&str) -> Vec<T>
+where
+    F: Fn(String) -> T,
+{
+    move |value| value.split_whitespace().map(|v| f(v.to_string())).collect()
+}
+
+// Parser for the `WWW-Authenticate` header grammar defined in
+// https://tools.ietf.org/html/rfc7235#section-4.1.
+struct ChallengeParser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+impl<'a> ChallengeParser<'a> {
+    fn new(input: &'a str) -> Result<Self, ChallengeParseError> {
+        if !input
+            .bytes()
+            .all(|b| b == b'\t' || (b' '..=b'~').contains(&b))
+        {
+            return Err(ChallengeParseError::NonAscii);
+        }
+        Ok(ChallengeParser {
+            input: input.as_bytes(),
+            pos: 0,
+        })
+    }
+
+    fn parse(mut self) -> Result<Vec<Challenge>, ChallengeParseError> {
+        let mut challenges = Vec::new();
+        loop {
+            self.skip_list_separators();
+            if self.peek().is_none() {
+                break Ok(challenges);
+            }
+            challenges.push(self.challenge()?);
+        }
+    }
+
+    fn challenge(&mut self) -> Result<Challenge, ChallengeParseError> {
+        let scheme = self.token(is_tchar);
+        if scheme.is_empty() {
+            return Err(self.error("expected authentication scheme"));
+        }
+        let mut challenge = Challenge::new(&scheme);
+
+        let mut first = true;
+        loop {
+            let start = self.pos;
+            let crossed_comma = self.skip_list_separators();
+            if self.peek().is_none() {
+                break;
+            }
+
+            let name = self.token(|b| is_tchar(b) || b == b'/');
+            if name.is_empty() {
+                return Err(self.error("expected parameter or token68"));
+            }
+            self.skip_whitespace();
+            let equals = self.count(b'=');
+            self.skip_whitespace();
+
+            if equals == 1 && !matches!(self.peek(), None | Some(b',')) {
+                if !name.bytes().all(is_tchar) {
+                    return Err(self.error("invalid parameter name"));
+                }
+                let value = if self.peek() == Some(b'"') {
+                    self.quoted_string()?
+                } else {
+                    let value = self.token(is_tchar);
+                    if value.is_empty() {
+                        return Err(self.error("expected parameter value"));
+                    }
+                    value
+                };
+                challenge.params.push((name.to_ascii_lowercase(), value));
+            } else if first && !crossed_comma {
+                // A token68 may only directly follow the scheme, separated by whitespace.
+                if !name.bytes().all(is_token68_char) {
+                    return Err(self.error("invalid token68"));
+                }
+                challenge.token68 = Some(format!("{}{}", name, "=".repeat(equals)));
+            } else if equals == 0 {
+                // Start of the next challenge.
+                self.pos = start;
+                break;
+            } else {
+                return Err(self.error("expected parameter value"));
+            }
+            first = false;
+        }
+
+        challenge.error = challenge
+            .param("error")
+            .map(BearerErrorResponseType::from_str);
+        Ok(challenge)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn error(&self, message: &'static str) -> ChallengeParseError {
+        ChallengeParseError::Syntax(self.pos, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    // Skips whitespace and empty list elements, returning `true` if a comma was consumed.
+    fn skip_list_separators(&mut self) -> bool {
+        let mut crossed_comma = false;
+        loop {
+            match self.peek() {
+                Some(b' ') | Some(b'\t') => {}
+                Some(b',') => crossed_comma = true,
+                _ => break crossed_comma,
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn count(&mut self, byte: u8) -> usize {
+        let start = self.pos;
+        while self.peek() == Some(byte) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    fn token<F>(&mut self, is_token_char: F) -> String
+    where
+        F: Fn(u8) -> bool,
+    {
+        let start = self.pos;
+        while self.peek().map(&is_token_char).unwrap_or(false) {
+            self.pos += 1;
+        }
+        String::from_utf8_lossy(&self.input[start..self.pos]).into_owned()
+    }
+
+    fn quoted_string(&mut self) -> Result<String, ChallengeParseError> {
+        // Skip the opening quote.
+        self.pos += 1;
+        let mut value = Vec::new();
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    break Ok(String::from_utf8_lossy(&value).into_owned());
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(escaped) => value.push(escaped),
+                        None => break Err(self.error("unterminated quoted string")),
+                    }
+                }
+                Some(b) => value.push(b),
+                None => break Err(self.error("unterminated quoted string")),
+            }
+            self.pos += 1;
+        }
+    }
+}
+
+fn is_tchar(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
+
+fn is_token68_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"-._~+/".contains(&b)
+}
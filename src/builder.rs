@@ -0,0 +1,357 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use super::credentials::CredentialProvider;
+use super::strict::StrictClient;
+use super::{
+    check_secure_url, AuthType, AuthUrl, Client, ClientId, ClientSecret, ConfigurationError,
+    DeviceAuthorizationUrl, ErrorResponse, IntrospectionUrl, RedirectUrl, RevocableToken,
+    RevocationUrl, TokenIntrospectionResponse, TokenResponse, TokenType, TokenUrl,
+};
+
+///
+/// OAuth2 grant (flow) that a client built by [`ClientBuilder`] intends to use.
+///
+/// Each grant determines which endpoint URLs [`ClientBuilder::build`] requires.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum GrantType {
+    ///
+    /// [Authorization Code Grant](https://tools.ietf.org/html/rfc6749#section-4.1), which
+    /// requires the token endpoint.
+    ///
+    AuthorizationCode,
+    ///
+    /// [Implicit Grant](https://tools.ietf.org/html/rfc6749#section-4.2), which requires no
+    /// endpoints other than the authorization endpoint.
+    ///
+    Implicit,
+    ///
+    /// [Resource Owner Password Credentials Grant](https://tools.ietf.org/html/rfc6749#section-4.3),
+    /// which requires the token endpoint.
+    ///
+    Password,
+    ///
+    /// [Client Credentials Grant](https://tools.ietf.org/html/rfc6749#section-4.4), which
+    /// requires the token endpoint.
+    ///
+    ClientCredentials,
+    ///
+    /// [Refreshing an Access Token](https://tools.ietf.org/html/rfc6749#section-6), which
+    /// requires the token endpoint.
+    ///
+    RefreshToken,
+    ///
+    /// [Device Authorization Grant](https://tools.ietf.org/html/rfc8628), which requires the
+    /// device authorization and token endpoints.
+    ///
+    DeviceCode,
+}
+impl GrantType {
+    fn requires_token_url(self) -> bool {
+        !matches!(self, GrantType::Implicit)
+    }
+}
+
+///
+/// Builder for a [`Client`] that validates the configuration up front, rather than failing
+/// when a request is made.
+///
+/// [`build`](Self::build) checks that:
+///  * Each endpoint URL uses HTTPS, except for plain HTTP to a loopback address (e.g., a local
+///    development server).
+///  * The redirect URL does not include a fragment, and uses either HTTPS, plain HTTP to a
+///    loopback address, or a private-use URI scheme (see
+///    [RFC 8252](https://tools.ietf.org/html/rfc8252#section-7)).
+///  * A client secret or credential provider is set if HTTP Basic authentication is explicitly
+///    requested. [`AuthType::RequestBody`] does not require a secret, since public clients
+///    authenticate using only their client ID. Client authentication methods based on a signing
+///    key, such as `private_key_jwt`
+///    ([RFC 7523](https://tools.ietf.org/html/rfc7523#section-2.2)), are not supported by
+///    [`AuthType`], so there is no signing key to validate.
+///  * The authorization endpoint URL is set, since every [`Client`] has one (see
+///    [`Client::new`]).
+///  * The endpoint URLs required by each enabled grant (see [`add_grant`](Self::add_grant)) are
+///    set. If no grants are enabled, the authorization code grant is assumed.
+///
+/// [`build_strict`](Self::build_strict) also checks that no grants removed by OAuth 2.1 are
+/// enabled, and returns a [`StrictClient`].
+///
+/// # Example
+///
+/// ```rust
+/// use oauth2::basic::BasicClientBuilder;
+/// use oauth2::{AuthUrl, ClientId, ClientSecret, GrantType, TokenUrl};
+///
+/// # fn err_wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = BasicClientBuilder::new(ClientId::new("client_id".to_string()))
+///     .set_client_secret(ClientSecret::new("client_secret".to_string()))
+///     .set_auth_uri(AuthUrl::new("https://example.com/authorize".to_string())?)
+///     .set_token_uri(TokenUrl::new("https://example.com/token".to_string())?)
+///     .add_grant(GrantType::ClientCredentials)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct ClientBuilder<TE, TR, TT, TIR, RT, TRE>
+where
+    TE: ErrorResponse,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT>,
+    RT: RevocableToken,
+    TRE: ErrorResponse,
+{
+    client_id: ClientId,
+    client_secret: Option<ClientSecret>,
+    credential_provider: Option<Arc<dyn CredentialProvider>>,
+    auth_url: Option<AuthUrl>,
+    auth_type: Option<AuthType>,
+    token_url: Option<TokenUrl>,
+    redirect_url: Option<RedirectUrl>,
+    introspection_url: Option<IntrospectionUrl>,
+    revocation_url: Option<RevocationUrl>,
+    device_authorization_url: Option<DeviceAuthorizationUrl>,
+    grants: Vec<GrantType>,
+    phantom: PhantomData<(TE, TR, TT, TIR, RT, TRE)>,
+}
+impl<TE, TR, TT, TIR, RT, TRE> ClientBuilder<TE, TR, TT, TIR, RT, TRE>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT>,
+    RT: RevocableToken,
+    TRE: ErrorResponse + 'static,
+{
+    ///
+    /// Creates a new builder for a client with the given client ID.
+    ///
+    pub fn new(client_id: ClientId) -> Self {
+        ClientBuilder {
+            client_id,
+            client_secret: None,
+            credential_provider: None,
+            auth_url: None,
+            auth_type: None,
+            token_url: None,
+            redirect_url: None,
+            introspection_url: None,
+            revocation_url: None,
+            device_authorization_url: None,
+            grants: Vec::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    ///
+    /// Sets the client secret used for authenticating the client to the authorization server.
+    ///
+    pub fn set_client_secret(mut self, client_secret: ClientSecret) -> Self {
+        self.client_secret = Some(client_secret);
+        self
+    }
+
+    ///
+    /// Sets a provider that supplies the client secret for each request (see
+    /// [`Client::set_credential_provider`]).
+    ///
+    pub fn set_credential_provider(mut self, provider: Arc<dyn CredentialProvider>) -> Self {
+        self.credential_provider = Some(provider);
+        self
+    }
+
+    ///
+    /// Sets the authorization endpoint URL, which is required.
+    ///
+    pub fn set_auth_uri(mut self, auth_url: AuthUrl) -> Self {
+        self.auth_url = Some(auth_url);
+        self
+    }
+
+    ///
+    /// Sets the token endpoint URL.
+    ///
+    pub fn set_token_uri(mut self, token_url: TokenUrl) -> Self {
+        self.token_url = Some(token_url);
+        self
+    }
+
+    ///
+    /// Configures the type of client authentication used for communicating with the
+    /// authorization server (see [`Client::set_auth_type`]).
+    ///
+    /// [`build`](Self::build) fails if HTTP Basic authentication is requested without a client
+    /// secret or credential provider. Signing key based methods such as `private_key_jwt` are out
+    /// of scope.
+    ///
+    pub fn set_auth_type(mut self, auth_type: AuthType) -> Self {
+        self.auth_type = Some(auth_type);
+        self
+    }
+
+    ///
+    /// Sets the redirect URL used by the authorization endpoint.
+    ///
+    pub fn set_redirect_uri(mut self, redirect_url: RedirectUrl) -> Self {
+        self.redirect_url = Some(redirect_url);
+        self
+    }
+
+    ///
+    /// Sets the introspection endpoint URL (see [`Client::set_introspection_uri`]).
+    ///
+    pub fn set_introspection_uri(mut self, introspection_url: IntrospectionUrl) -> Self {
+        self.introspection_url = Some(introspection_url);
+        self
+    }
+
+    ///
+    /// Sets the revocation endpoint URL (see [`Client::set_revocation_uri`]).
+    ///
+    pub fn set_revocation_uri(mut self, revocation_url: RevocationUrl) -> Self {
+        self.revocation_url = Some(revocation_url);
+        self
+    }
+
+    ///
+    /// Sets the device authorization endpoint URL (see
+    /// [`Client::set_device_authorization_url`]).
+    ///
+    pub fn set_device_authorization_url(
+        mut self,
+        device_authorization_url: DeviceAuthorizationUrl,
+    ) -> Self {
+        self.device_authorization_url = Some(device_authorization_url);
+        self
+    }
+
+    ///
+    /// Enables a grant that the client intends to use, whose required endpoint URLs are checked
+    /// by [`build`](Self::build).
+    ///
+    pub fn add_grant(mut self, grant: GrantType) -> Self {
+        if !self.grants.contains(&grant) {
+            self.grants.push(grant);
+        }
+        self
+    }
+
+    ///
+    /// Validates the configuration and returns the resulting client.
+    ///
+    pub fn build(self) -> Result<Client<TE, TR, TT, TIR, RT, TRE>, ConfigurationError> {
+        self.check_urls()?;
+        self.check_grants()?;
+
+        let auth_url = self
+            .auth_url
+            .ok_or(ConfigurationError::MissingUrl("authorization"))?;
+
+        let auth_type = match self.auth_type {
+            Some(AuthType::BasicAuth)
+                if self.client_secret.is_none() && self.credential_provider.is_none() =>
+            {
+                return Err(ConfigurationError::MissingClientSecret);
+            }
+            Some(auth_type) => auth_type,
+            None => AuthType::BasicAuth,
+        };
+
+        Ok(Client {
+            client_id: self.client_id,
+            client_secret: self.client_secret,
+            credential_provider: self.credential_provider,
+            auth_url,
+            auth_type,
+            token_url: self.token_url,
+            redirect_url: self.redirect_url,
+            introspection_url: self.introspection_url,
+            revocation_url: self.revocation_url,
+            device_authorization_url: self.device_authorization_url,
+            strict: false,
+            phantom: PhantomData,
+        })
+    }
+
+    ///
+    /// Validates the configuration, including the grants supported in strict mode, and returns
+    /// the resulting client with strict mode enabled (see [`Client::set_strict_mode`]).
+    ///
+    pub fn build_strict(
+        self,
+    ) -> Result<StrictClient<TE, TR, TT, TIR, RT, TRE>, ConfigurationError> {
+        for grant in &self.grants {
+            match grant {
+                GrantType::Implicit => {
+                    return Err(ConfigurationError::UnsupportedFlow("implicit"));
+                }
+                GrantType::Password => {
+                    return Err(ConfigurationError::UnsupportedFlow(
+                        "resource owner password credentials",
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(self.build()?.set_strict_mode())
+    }
+
+    fn check_urls(&self) -> Result<(), ConfigurationError> {
+        if let Some(ref auth_url) = self.auth_url {
+            check_secure_url(auth_url.url(), "authorization")?;
+        }
+        if let Some(ref token_url) = self.token_url {
+            check_secure_url(token_url.url(), "token")?;
+        }
+        if let Some(ref introspection_url) = self.introspection_url {
+            check_secure_url(introspection_url.url(), "introspection")?;
+        }
+        if let Some(ref device_authorization_url) = self.device_authorization_url {
+            check_secure_url(device_authorization_url.url(), "device authorization")?;
+        }
+        if let Some(ref revocation_url) = self.revocation_url {
+            check_secure_url(revocation_url.url(), "revocation")?;
+        }
+
+        if let Some(ref redirect_url) = self.redirect_url {
+            // See https://tools.ietf.org/html/rfc6749#section-3.1.2.
+            if redirect_url.url().fragment().is_some() {
+                return Err(ConfigurationError::InvalidRedirectUrl(
+                    "must not include a fragment",
+                ));
+            }
+            // Private-use URI schemes are permitted for native apps (see
+            // https://tools.ietf.org/html/rfc8252#section-7.1).
+            if redirect_url.url().scheme() == "http" {
+                check_secure_url(redirect_url.url(), "redirect")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_grants(&self) -> Result<(), ConfigurationError> {
+        let default_grants = [GrantType::AuthorizationCode];
+        let grants = if self.grants.is_empty() {
+            &default_grants[..]
+        } else {
+            &self.grants[..]
+        };
+
+        for grant in grants {
+            if grant.requires_token_url() && self.token_url.is_none() {
+                return Err(ConfigurationError::MissingUrl("token"));
+            }
+            if *grant == GrantType::DeviceCode && self.device_authorization_url.is_none() {
+                return Err(ConfigurationError::MissingUrl("device authorization"));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,302 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::Debug;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use serde::Deserialize;
+
+use crate::{ClientSecret, ErrorResponse, HttpResponse, RequestTokenError};
+
+///
+/// Error encountered while resolving a client secret.
+///
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum CredentialError {
+    ///
+    /// Failed to read the file containing the client secret.
+    ///
+    #[error("Failed to read client secret from {}", _0.display())]
+    Io(PathBuf, #[source] io::Error),
+    ///
+    /// The environment variable containing the client secret is not set.
+    ///
+    #[error("Environment variable `{0}` containing the client secret is not set")]
+    MissingEnvVar(String),
+    ///
+    /// Other error, returned by custom credential providers.
+    ///
+    #[error("{0}")]
+    Other(String),
+}
+
+///
+/// Source of the client secret used for authenticating the client to the authorization server.
+///
+/// A [`Client`](crate::Client) configured with a credential provider (see
+/// [`Client::set_credential_provider`](crate::Client::set_credential_provider)) calls
+/// [`client_secret`](Self::client_secret) for each request, which allows the secret to be rotated
+/// without rebuilding the client.
+///
+pub trait CredentialProvider: Debug + Send + Sync {
+    ///
+    /// Returns the client secret to use for the next request, or `None` if the client should
+    /// authenticate without a secret.
+    ///
+    fn client_secret(&self) -> Result<Option<ClientSecret>, CredentialError>;
+
+    ///
+    /// Called when the authorization server rejects `rejected` with an `invalid_client` error.
+    ///
+    /// Returns `true` if a different secret will be returned by subsequent calls to
+    /// [`client_secret`](Self::client_secret). Device access token polling then retries
+    /// immediately with the new secret. Other requests still return the `invalid_client` error,
+    /// and the caller may re-issue the request to use the new secret. The default implementation
+    /// returns `false`.
+    ///
+    fn invalid_client(&self, rejected: &ClientSecret) -> bool {
+        let _ = rejected;
+        false
+    }
+}
+
+///
+/// Credential provider that reads the client secret from an environment variable for each
+/// request.
+///
+#[derive(Clone, Debug)]
+pub struct EnvCredentialProvider {
+    name: String,
+}
+impl EnvCredentialProvider {
+    ///
+    /// Creates a provider that reads the client secret from the environment variable `name`.
+    ///
+    pub fn new<N>(name: N) -> Self
+    where
+        N: Into<String>,
+    {
+        EnvCredentialProvider { name: name.into() }
+    }
+}
+impl CredentialProvider for EnvCredentialProvider {
+    fn client_secret(&self) -> Result<Option<ClientSecret>, CredentialError> {
+        std::env::var(&self.name)
+            .map(|secret| Some(ClientSecret::new(secret)))
+            .map_err(|_| CredentialError::MissingEnvVar(self.name.clone()))
+    }
+}
+
+///
+/// Credential provider that reads the client secret from a file (e.g., a mounted container
+/// secret) for each request.
+///
+/// Trailing newlines are removed from the file contents.
+///
+#[derive(Clone, Debug)]
+pub struct FileCredentialProvider {
+    path: PathBuf,
+}
+impl FileCredentialProvider {
+    ///
+    /// Creates a provider that reads the client secret from the file at `path`.
+    ///
+    pub fn new<P>(path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        FileCredentialProvider { path: path.into() }
+    }
+}
+impl CredentialProvider for FileCredentialProvider {
+    fn client_secret(&self) -> Result<Option<ClientSecret>, CredentialError> {
+        let secret = fs::read_to_string(&self.path)
+            .map_err(|err| CredentialError::Io(self.path.clone(), err))?;
+        Ok(Some(ClientSecret::new(
+            secret.trim_end_matches(&['\n', '\r'][..]).to_string(),
+        )))
+    }
+}
+
+#[derive(Debug)]
+struct RotatingSecrets {
+    primary: ClientSecret,
+    secondary: Option<ClientSecret>,
+}
+
+///
+/// In-memory credential provider supporting a secondary secret during a rotation window.
+///
+/// Requests use the primary secret. If the authorization server rejects the primary secret with
+/// an `invalid_client` error, the secondary secret (if any) is promoted to primary, and the
+/// previous primary secret becomes the secondary.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+///
+/// use oauth2::basic::BasicClient;
+/// use oauth2::credentials::RotatingCredentialProvider;
+/// use oauth2::{AuthUrl, ClientId, ClientSecret, TokenUrl};
+///
+/// # fn err_wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// let credentials = Arc::new(RotatingCredentialProvider::new(ClientSecret::new(
+///     "current_secret".to_string(),
+/// )));
+/// let client = BasicClient::new(
+///     ClientId::new("client_id".to_string()),
+///     None,
+///     AuthUrl::new("https://example.com/auth".to_string())?,
+///     Some(TokenUrl::new("https://example.com/token".to_string())?),
+/// )
+/// .set_credential_provider(credentials.clone());
+///
+/// // Later, once the new secret has been issued by the authorization server:
+/// credentials.rotate(ClientSecret::new("new_secret".to_string()));
+/// # Ok(())
+/// # }
+/// ```
+///
+#[derive(Debug)]
+pub struct RotatingCredentialProvider {
+    secrets: RwLock<RotatingSecrets>,
+}
+impl RotatingCredentialProvider {
+    ///
+    /// Creates a provider with the given primary secret and no secondary secret.
+    ///
+    pub fn new(primary: ClientSecret) -> Self {
+        RotatingCredentialProvider {
+            secrets: RwLock::new(RotatingSecrets {
+                primary,
+                secondary: None,
+            }),
+        }
+    }
+
+    ///
+    /// Returns the primary secret, which is used for requests.
+    ///
+    pub fn primary(&self) -> ClientSecret {
+        self.secrets.read().unwrap().primary.clone()
+    }
+
+    ///
+    /// Returns the secondary secret, if any.
+    ///
+    pub fn secondary(&self) -> Option<ClientSecret> {
+        self.secrets.read().unwrap().secondary.clone()
+    }
+
+    ///
+    /// Sets the secondary secret, which is promoted if the primary secret is rejected (e.g., a
+    /// new secret that the authorization server is expected to begin accepting soon).
+    ///
+    pub fn set_secondary(&self, secondary: Option<ClientSecret>) {
+        self.secrets.write().unwrap().secondary = secondary;
+    }
+
+    ///
+    /// Makes `new_secret` the primary secret, keeping the previous primary secret as the
+    /// secondary until the authorization server stops accepting it.
+    ///
+    pub fn rotate(&self, new_secret: ClientSecret) {
+        let mut secrets = self.secrets.write().unwrap();
+        let previous = std::mem::replace(&mut secrets.primary, new_secret);
+        secrets.secondary = Some(previous);
+    }
+}
+impl CredentialProvider for RotatingCredentialProvider {
+    fn client_secret(&self) -> Result<Option<ClientSecret>, CredentialError> {
+        Ok(Some(self.primary()))
+    }
+
+    fn invalid_client(&self, rejected: &ClientSecret) -> bool {
+        let mut secrets = self.secrets.write().unwrap();
+        if secrets.primary.secret() != rejected.secret() {
+            // Another request has already promoted the secondary secret.
+            return true;
+        }
+        match secrets.secondary.take() {
+            Some(secondary) => {
+                let previous = std::mem::replace(&mut secrets.primary, secondary);
+                secrets.secondary = Some(previous);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+///
+/// Client secret configured on a `Client`, either statically or via a credential provider.
+///
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ClientSecretSource<'a> {
+    pub(crate) client_secret: Option<&'a ClientSecret>,
+    pub(crate) provider: Option<&'a dyn CredentialProvider>,
+}
+impl<'a> ClientSecretSource<'a> {
+    pub(crate) fn resolve<RE, TE>(
+        self,
+    ) -> Result<ResolvedClientSecret<'a>, RequestTokenError<RE, TE>>
+    where
+        RE: Error + 'static,
+        TE: ErrorResponse,
+    {
+        let secret = match self.provider {
+            Some(provider) => provider
+                .client_secret()
+                .map_err(|err| RequestTokenError::Other(err.to_string()))?
+                .map(Cow::Owned),
+            None => self.client_secret.map(Cow::Borrowed),
+        };
+        Ok(ResolvedClientSecret {
+            secret,
+            provider: self.provider,
+        })
+    }
+}
+
+///
+/// Client secret used for a single request.
+///
+pub(crate) struct ResolvedClientSecret<'a> {
+    secret: Option<Cow<'a, ClientSecret>>,
+    provider: Option<&'a dyn CredentialProvider>,
+}
+impl<'a> ResolvedClientSecret<'a> {
+    pub(crate) fn secret(&self) -> Option<&ClientSecret> {
+        self.secret.as_deref()
+    }
+
+    ///
+    /// Notifies the credential provider if the authorization server rejected the secret, and
+    /// returns whether the request should be retried with a different secret.
+    ///
+    pub(crate) fn check_response(&self, http_response: &HttpResponse) -> bool {
+        match (self.provider, self.secret.as_ref()) {
+            (Some(provider), Some(secret)) if is_invalid_client(http_response) => {
+                provider.invalid_client(secret)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn is_invalid_client(http_response: &HttpResponse) -> bool {
+    #[derive(Deserialize)]
+    struct ErrorCode {
+        error: String,
+    }
+
+    // See https://tools.ietf.org/html/rfc6749#section-5.2.
+    !http_response.status_code.is_success()
+        && serde_json::from_slice::<ErrorCode>(&http_response.body)
+            .map(|response| response.error == "invalid_client")
+            .unwrap_or(false)
+}
@@ -1,9 +1,22 @@
+use std::collections::HashMap;
+use std::future::Future;
 use std::io::Read;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::Duration;
 
-use curl::easy::Easy;
+use curl_crate as curl;
+
+use curl::easy::{Easy2, Handler, ReadError, WriteError};
+use curl::multi::{Easy2Handle, Multi};
 use http::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use http::method::Method;
 use http::status::StatusCode;
+use once_cell::sync::OnceCell;
 
 use super::{HttpRequest, HttpResponse};
 
@@ -27,58 +40,91 @@ pub enum Error {
 /// Synchronous HTTP client.
 ///
 pub fn http_client(request: HttpRequest) -> Result<HttpResponse, Error> {
-    let mut easy = Easy::new();
+    let easy = new_easy(request)?;
+    easy.perform().map_err(Error::Curl)?;
+    into_response(easy)
+}
+
+///
+/// Asynchronous HTTP client.
+///
+/// Requests are performed by a single background thread that drives all transfers through a
+/// shared curl multi handle, so awaiting the response never blocks the executor. Dropping the
+/// returned future aborts the transfer.
+///
+/// Unless the `curl-multi-wakeup` feature (which requires libcurl 7.68.0 or later) is enabled,
+/// the background thread notices requests submitted or cancelled while other transfers are in
+/// progress only when it next polls, so a request may wait up to one second before it is sent.
+///
+/// Failures of the shared multi handle are returned as [`Error::Other`].
+///
+pub async fn async_http_client(request: HttpRequest) -> Result<HttpResponse, Error> {
+    let easy = new_easy(request)?;
+    Driver::shared()?.submit(easy)?.await
+}
+
+// Maximum amount of time the driver thread blocks waiting for transfer activity. curl returns
+// earlier when one of its internal timers expires. With the `curl-multi-wakeup` feature,
+// submitting or cancelling a transfer also interrupts the wait (see `Wakeup`). Otherwise, the
+// driver notices these within this timeout.
+const MULTI_WAIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+struct Collector {
+    body: Vec<u8>,
+    position: usize,
+    data: Vec<u8>,
+}
+impl Handler for Collector {
+    fn write(&mut self, data: &[u8]) -> Result<usize, WriteError> {
+        self.data.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, ReadError> {
+        let read = (&self.body[self.position..]).read(buf).unwrap_or(0);
+        self.position += read;
+        Ok(read)
+    }
+}
+
+fn new_easy(request: HttpRequest) -> Result<Easy2<Collector>, Error> {
+    let mut easy = Easy2::new(Collector {
+        body: request.body,
+        position: 0,
+        data: Vec::new(),
+    });
     easy.url(&request.url.to_string()[..])
         .map_err(Error::Curl)?;
 
     let mut headers = curl::easy::List::new();
-    request
-        .headers
-        .iter()
-        .map(|(name, value)| {
-            headers
-                .append(&format!(
-                    "{}: {}",
+    for (name, value) in request.headers.iter() {
+        headers
+            .append(&format!(
+                "{}: {}",
+                name,
+                value.to_str().map_err(|_| Error::Other(format!(
+                    "invalid {} header value {:?}",
                     name,
-                    value.to_str().map_err(|_| Error::Other(format!(
-                        "invalid {} header value {:?}",
-                        name,
-                        value.as_bytes()
-                    )))?
-                ))
-                .map_err(Error::Curl)
-        })
-        .collect::<Result<_, _>>()?;
+                    value.as_bytes()
+                )))?
+            ))
+            .map_err(Error::Curl)?;
+    }
 
     easy.http_headers(headers).map_err(Error::Curl)?;
 
     if let Method::POST = request.method {
         easy.post(true).map_err(Error::Curl)?;
-        easy.post_field_size(request.body.len() as u64)
+        easy.post_field_size(easy.get_ref().body.len() as u64)
             .map_err(Error::Curl)?;
     } else {
         assert_eq!(request.method, Method::GET);
     }
 
-    let mut form_slice = &request.body[..];
-    let mut data = Vec::new();
-    {
-        let mut transfer = easy.transfer();
-
-        transfer
-            .read_function(|buf| Ok(form_slice.read(buf).unwrap_or(0)))
-            .map_err(Error::Curl)?;
-
-        transfer
-            .write_function(|new_data| {
-                data.extend_from_slice(new_data);
-                Ok(new_data.len())
-            })
-            .map_err(Error::Curl)?;
-
-        transfer.perform().map_err(Error::Curl)?;
-    }
+    Ok(easy)
+}
 
+fn into_response(mut easy: Easy2<Collector>) -> Result<HttpResponse, Error> {
     let status_code = easy.response_code().map_err(Error::Curl)? as u16;
 
     Ok(HttpResponse {
@@ -96,6 +142,251 @@ pub fn http_client(request: HttpRequest) -> Result<HttpResponse, Error> {
             })
             .transpose()?
             .unwrap_or_else(HeaderMap::new),
-        body: data,
+        body: std::mem::take(&mut easy.get_mut().data),
     })
 }
+
+#[derive(Default)]
+struct TransferState {
+    result: Option<Result<HttpResponse, Error>>,
+    waker: Option<Waker>,
+}
+
+#[derive(Default)]
+struct SharedTransfer {
+    state: Mutex<TransferState>,
+    cancelled: AtomicBool,
+}
+impl SharedTransfer {
+    fn complete(&self, result: Result<HttpResponse, Error>) {
+        let mut state = self.state.lock().unwrap();
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+struct Transfer {
+    easy: Easy2<Collector>,
+    shared: Arc<SharedTransfer>,
+}
+
+///
+/// Background thread that performs every asynchronous transfer through a single multi handle.
+///
+struct Driver {
+    // `Sender` is not `Sync`.
+    transfers: Mutex<Sender<Transfer>>,
+    wakeup: Wakeup,
+}
+impl Driver {
+    fn shared() -> Result<&'static Driver, Error> {
+        static DRIVER: OnceCell<Driver> = OnceCell::new();
+        DRIVER.get_or_try_init(Driver::spawn)
+    }
+
+    fn spawn() -> Result<Self, Error> {
+        let (sender, receiver) = mpsc::channel();
+        // `Multi` is not `Send`, so it is created by the driver thread, which returns the
+        // `Wakeup` for interrupting it.
+        let (wakeup_sender, wakeup_receiver) = mpsc::sync_channel(1);
+        thread::Builder::new()
+            .name("oauth2-curl".to_string())
+            .spawn(move || {
+                let multi = Multi::new();
+                if wakeup_sender.send(Wakeup::new(&multi)).is_ok() {
+                    drive(multi, receiver);
+                }
+            })
+            .map_err(|err| Error::Other(format!("failed to spawn curl thread: {}", err)))?;
+        let wakeup = wakeup_receiver
+            .recv()
+            .map_err(|_| Error::Other("curl thread exited".to_string()))?;
+
+        Ok(Driver {
+            transfers: Mutex::new(sender),
+            wakeup,
+        })
+    }
+
+    fn submit(&'static self, easy: Easy2<Collector>) -> Result<MultiTransfer, Error> {
+        let shared = Arc::new(SharedTransfer::default());
+        self.transfers
+            .lock()
+            .unwrap()
+            .send(Transfer {
+                easy,
+                shared: shared.clone(),
+            })
+            .map_err(|_| Error::Other("curl thread exited".to_string()))?;
+        self.wakeup.wake();
+
+        Ok(MultiTransfer {
+            driver: self,
+            shared,
+        })
+    }
+}
+
+struct MultiTransfer {
+    driver: &'static Driver,
+    shared: Arc<SharedTransfer>,
+}
+impl Future for MultiTransfer {
+    type Output = Result<HttpResponse, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.shared.state.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+impl Drop for MultiTransfer {
+    fn drop(&mut self) {
+        self.shared.cancelled.store(true, Ordering::SeqCst);
+        self.driver.wakeup.wake();
+    }
+}
+
+///
+/// Interrupts the driver thread while it waits for transfer activity.
+///
+#[cfg(feature = "curl-multi-wakeup")]
+struct Wakeup(curl::multi::MultiWaker);
+#[cfg(feature = "curl-multi-wakeup")]
+impl Wakeup {
+    fn new(multi: &Multi) -> Self {
+        Wakeup(multi.waker())
+    }
+
+    fn wake(&self) {
+        // Fails only if the driver thread has exited, in which case there is nothing to wake.
+        let _ = self.0.wakeup();
+    }
+}
+
+///
+/// Without `curl_multi_wakeup` (libcurl 7.68.0), the driver thread polls for submitted and
+/// cancelled transfers every `MULTI_WAIT_TIMEOUT`.
+///
+#[cfg(not(feature = "curl-multi-wakeup"))]
+struct Wakeup;
+#[cfg(not(feature = "curl-multi-wakeup"))]
+impl Wakeup {
+    fn new(_multi: &Multi) -> Self {
+        Wakeup
+    }
+
+    fn wake(&self) {}
+}
+
+#[cfg(feature = "curl-multi-wakeup")]
+fn wait(multi: &Multi) -> Result<u32, curl::MultiError> {
+    multi.poll(&mut [], MULTI_WAIT_TIMEOUT)
+}
+
+#[cfg(not(feature = "curl-multi-wakeup"))]
+fn wait(multi: &Multi) -> Result<u32, curl::MultiError> {
+    multi.wait(&mut [], MULTI_WAIT_TIMEOUT)
+}
+
+type ActiveTransfers = HashMap<usize, (Easy2Handle<Collector>, Arc<SharedTransfer>)>;
+
+fn drive(multi: Multi, receiver: Receiver<Transfer>) {
+    let mut active = ActiveTransfers::new();
+    let mut next_token = 0usize;
+
+    loop {
+        // Block until a transfer is submitted while there is nothing else to do.
+        let mut submitted = Vec::new();
+        if active.is_empty() {
+            match receiver.recv() {
+                Ok(transfer) => submitted.push(transfer),
+                Err(_) => return,
+            }
+        }
+        while let Ok(transfer) = receiver.try_recv() {
+            submitted.push(transfer);
+        }
+
+        for transfer in submitted {
+            let mut handle = match multi.add2(transfer.easy) {
+                Ok(handle) => handle,
+                Err(err) => {
+                    transfer.shared.complete(Err(multi_error(&err)));
+                    continue;
+                }
+            };
+            if let Err(err) = handle.set_token(next_token) {
+                let _ = multi.remove2(handle);
+                transfer.shared.complete(Err(Error::Curl(err)));
+                continue;
+            }
+            active.insert(next_token, (handle, transfer.shared));
+            next_token = next_token.wrapping_add(1);
+        }
+
+        // Abort transfers whose futures have been dropped.
+        let cancelled = active
+            .iter()
+            .filter(|(_, (_, shared))| shared.cancelled.load(Ordering::SeqCst))
+            .map(|(token, _)| *token)
+            .collect::<Vec<_>>();
+        for token in cancelled {
+            if let Some((handle, _)) = active.remove(&token) {
+                let _ = multi.remove2(handle);
+            }
+        }
+
+        if let Err(err) = multi.perform() {
+            fail_all(&multi, &mut active, &err);
+            continue;
+        }
+
+        let mut finished = Vec::new();
+        multi.messages(|message| {
+            let token = match message.token() {
+                Ok(token) => token,
+                Err(_) => return,
+            };
+            if let Some((handle, _)) = active.get(&token) {
+                if let Some(result) = message.result_for2(handle) {
+                    finished.push((token, result));
+                }
+            }
+        });
+        for (token, result) in finished {
+            if let Some((handle, shared)) = active.remove(&token) {
+                shared.complete(match multi.remove2(handle) {
+                    Ok(easy) => result
+                        .map_err(Error::Curl)
+                        .and_then(|()| into_response(easy)),
+                    Err(err) => Err(multi_error(&err)),
+                });
+            }
+        }
+
+        if !active.is_empty() {
+            if let Err(err) = wait(&multi) {
+                fail_all(&multi, &mut active, &err);
+            }
+        }
+    }
+}
+
+fn multi_error(err: &curl::MultiError) -> Error {
+    Error::Other(format!("curl multi request failed: {}", err))
+}
+
+fn fail_all(multi: &Multi, active: &mut ActiveTransfers, err: &curl::MultiError) {
+    for (_, (handle, shared)) in active.drain() {
+        let _ = multi.remove2(handle);
+        shared.complete(Err(multi_error(err)));
+    }
+}
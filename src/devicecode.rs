@@ -1,15 +1,15 @@
 use std::error::Error;
 use std::fmt::Error as FormatterError;
 use std::fmt::{Debug, Display, Formatter};
-use std::marker::PhantomData;
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    DeviceCode, EndUserVerificationUrl, ErrorResponse, ErrorResponseType, RequestTokenError,
-    StandardErrorResponse, TokenResponse, TokenType, UserCode,
+    endpoint_response, DeviceCode, EndUserVerificationUrl, ErrorResponseType, HttpResponse,
+    RequestTokenError, StandardErrorResponse, UserCode,
 };
 use crate::basic::BasicErrorResponseType;
 use crate::types::VerificationUriComplete;
@@ -71,6 +71,17 @@ where
     #[serde(default = "default_devicecode_interval")]
     interval: u64,
 
+    /// The time at which the response was received, which determines when the
+    /// "device_code" and "user_code" expire. This field is not part of the
+    /// authorization server's response: it is set by
+    /// [`DeviceAuthorizationRequest`](crate::DeviceAuthorizationRequest) when the
+    /// response is received, and is retained when the response is serialized
+    /// (e.g., to persist a pending device authorization flow). It is stored
+    /// under the `oauth2_issued_at` key so that an `issued_at` member returned
+    /// by the authorization server is left to the extra fields.
+    #[serde(rename = "oauth2_issued_at", skip_serializing_if = "Option::is_none")]
+    issued_at: Option<DateTime<Utc>>,
+
     #[serde(bound = "EF: ExtraDeviceAuthorizationFields", flatten)]
     extra_fields: EF,
 }
@@ -115,6 +126,34 @@ where
         Duration::from_secs(self.interval)
     }
 
+    /// The time at which the response was received, or `None` if the response
+    /// was not received through
+    /// [`DeviceAuthorizationRequest`](crate::DeviceAuthorizationRequest) (e.g.,
+    /// if it was deserialized directly from the authorization server's
+    /// response).
+    pub fn issued_at(&self) -> Option<DateTime<Utc>> {
+        self.issued_at
+    }
+
+    /// Set the time at which the response was received (e.g., if the response
+    /// was deserialized some time after it was received).
+    pub fn set_issued_at(&mut self, issued_at: DateTime<Utc>) {
+        self.issued_at = Some(issued_at);
+    }
+
+    /// The time at which the "device_code" and "user_code" expire, or `None`
+    /// if the issuance time is unknown or the expiration time is not
+    /// representable.
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at_from(self.issued_at?)
+    }
+
+    pub(crate) fn expires_at_from(&self, issued_at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        chrono::Duration::from_std(self.expires_in())
+            .ok()
+            .and_then(|expires_in| issued_at.checked_add_signed(expires_in))
+    }
+
     /// Any extra fields returned on the response.
     pub fn extra_fields(&self) -> &EF {
         &self.extra_fields
@@ -227,13 +266,295 @@ impl Display for DeviceCodeErrorResponseType {
 ///
 pub type DeviceCodeErrorResponse = StandardErrorResponse<DeviceCodeErrorResponseType>;
 
-pub(crate) enum DeviceAccessTokenPollResult<TR, RE, TE, TT>
+///
+/// Error encountered while polling for a device access token.
+///
+/// Distinguishes the outcomes that typically require a specific response from the application:
+/// restarting the flow after the device code expires, or informing the user that the request was
+/// denied.
+///
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum DeviceAccessTokenError<RE>
+where
+    RE: Error + 'static,
+{
+    ///
+    /// The device code expired (or the polling timeout elapsed) before the end user completed
+    /// authorization, so polling stopped without contacting the authorization server.
+    ///
+    #[error("Device code expired")]
+    Expired,
+    ///
+    /// The authorization server reported that the device code expired (`expired_token`).
+    ///
+    #[error("Server reported that the device code expired")]
+    ExpiredToken(DeviceCodeErrorResponse),
+    ///
+    /// The end user denied the authorization request (`access_denied`).
+    ///
+    #[error("Authorization request was denied")]
+    AccessDenied(DeviceCodeErrorResponse),
+    ///
+    /// Any other error.
+    ///
+    #[error(transparent)]
+    Request(RequestTokenError<RE, DeviceCodeErrorResponse>),
+}
+impl<RE> From<RequestTokenError<RE, DeviceCodeErrorResponse>> for DeviceAccessTokenError<RE>
+where
+    RE: Error + 'static,
+{
+    fn from(err: RequestTokenError<RE, DeviceCodeErrorResponse>) -> Self {
+        match err {
+            RequestTokenError::ServerResponse(dcer) => match dcer.error() {
+                DeviceCodeErrorResponseType::ExpiredToken => {
+                    DeviceAccessTokenError::ExpiredToken(dcer)
+                }
+                DeviceCodeErrorResponseType::AccessDenied => {
+                    DeviceAccessTokenError::AccessDenied(dcer)
+                }
+                _ => DeviceAccessTokenError::Request(RequestTokenError::ServerResponse(dcer)),
+            },
+            err => DeviceAccessTokenError::Request(err),
+        }
+    }
+}
+impl<RE> From<DeviceAccessTokenError<RE>> for RequestTokenError<RE, DeviceCodeErrorResponse>
+where
+    RE: Error + 'static,
+{
+    fn from(err: DeviceAccessTokenError<RE>) -> Self {
+        match err {
+            DeviceAccessTokenError::Expired => {
+                RequestTokenError::Other("Device code expired".to_string())
+            }
+            DeviceAccessTokenError::ExpiredToken(dcer)
+            | DeviceAccessTokenError::AccessDenied(dcer) => RequestTokenError::ServerResponse(dcer),
+            DeviceAccessTokenError::Request(err) => err,
+        }
+    }
+}
+
+///
+/// Outcome of a single device access token poll (see
+/// [`DeviceAccessTokenPollState::process_response`]).
+///
+#[derive(Debug)]
+pub enum DeviceAccessTokenPollStatus<TR, RE>
 where
-    TE: ErrorResponse + 'static,
-    TR: TokenResponse<TT>,
-    TT: TokenType,
     RE: Error + 'static,
 {
-    ContinueWithNewPollInterval(Duration),
-    Done(Result<TR, RequestTokenError<RE, TE>>, PhantomData<TT>),
+    ///
+    /// The end user has not yet completed authorization. The client should poll again after the
+    /// given interval, which is unchanged.
+    ///
+    Pending(Duration),
+    ///
+    /// The authorization server requested that the client poll less frequently. The client should
+    /// poll again after the given (increased) interval.
+    ///
+    SlowDown(Duration),
+    ///
+    /// The request failed to reach the authorization server, which is treated as transient. The
+    /// client should poll again after the given (increased) interval.
+    ///
+    TransientError(RE, Duration),
+    ///
+    /// The end user authorized the client, and the authorization server issued a token.
+    ///
+    Done(TR),
+    ///
+    /// The device authorization flow failed (e.g., the end user denied the request or the device
+    /// code expired) and should not be polled again.
+    ///
+    Failed(DeviceAccessTokenError<RE>),
+}
+
+///
+/// Progress of a device authorization flow, reported by
+/// [`DeviceAccessTokenRequest::request_stream`](crate::DeviceAccessTokenRequest::request_stream).
+///
+#[derive(Debug)]
+pub enum DeviceAccessTokenEvent<TR, RE>
+where
+    RE: Error + 'static,
+{
+    ///
+    /// The end user has not yet completed authorization. The next poll occurs after the given
+    /// interval.
+    ///
+    Polling(Duration),
+    ///
+    /// The authorization server requested that the client poll less frequently. The next poll
+    /// occurs after the given (increased) interval.
+    ///
+    SlowDown(Duration),
+    ///
+    /// The request failed to reach the authorization server. The next poll occurs after the given
+    /// (increased) interval.
+    ///
+    TransientError(RE, Duration),
+    ///
+    /// The flow expired before the end user completed authorization. This is the final event.
+    ///
+    Expired,
+    ///
+    /// The end user authorized the client, and the authorization server issued a token. This is
+    /// the final event.
+    ///
+    Completed(TR),
+    ///
+    /// The device authorization flow failed (e.g., the end user denied the request). This is the
+    /// final event.
+    ///
+    Failed(DeviceAccessTokenError<RE>),
+}
+
+///
+/// State of a pending device authorization flow, which determines when to poll the token
+/// endpoint and interprets each response.
+///
+/// This type is independent of how requests are sent and how the client waits between polls,
+/// which allows applications to render progress (e.g., a countdown until the user code expires),
+/// cancel the flow, or persist it (along with the [`DeviceAuthorizationResponse`]) across
+/// restarts. [`DeviceAccessTokenRequest::request`](crate::DeviceAccessTokenRequest::request) and
+/// [`DeviceAccessTokenRequest::request_async`](crate::DeviceAccessTokenRequest::request_async)
+/// use this state machine internally.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use oauth2::basic::BasicClient;
+/// use oauth2::devicecode::{
+///     DeviceAccessTokenPollStatus, StandardDeviceAuthorizationResponse,
+/// };
+/// use oauth2::reqwest::http_client;
+/// use oauth2::{EndpointRequest, RequestTokenError};
+///
+/// # fn err_wrapper(
+/// #     client: BasicClient,
+/// #     details: StandardDeviceAuthorizationResponse,
+/// # ) -> Result<(), Box<dyn std::error::Error>> {
+/// type HttpError = oauth2::reqwest::Error<reqwest::Error>;
+///
+/// let request = client.exchange_device_access_token(&details);
+/// let mut state = request.poll_state::<HttpError>(None)?;
+///
+/// let token = loop {
+///     if state.is_expired(chrono::Utc::now()) {
+///         return Err("device code expired".into());
+///     }
+///     let response = http_client(request.prepare::<HttpError>()?);
+///     match state.process_response(response) {
+///         DeviceAccessTokenPollStatus::Pending(_)
+///         | DeviceAccessTokenPollStatus::SlowDown(_)
+///         | DeviceAccessTokenPollStatus::TransientError(..) => {
+///             std::thread::sleep(state.interval())
+///         }
+///         DeviceAccessTokenPollStatus::Done(token) => break token,
+///         DeviceAccessTokenPollStatus::Failed(err) => return Err(err.into()),
+///     }
+/// };
+/// # let _: oauth2::basic::BasicTokenResponse = token;
+/// # Ok(())
+/// # }
+/// ```
+///
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DeviceAccessTokenPollState {
+    interval: Duration,
+    expires_at: DateTime<Utc>,
+}
+impl DeviceAccessTokenPollState {
+    ///
+    /// Creates a new state that initially polls every `interval` until `expires_at`.
+    ///
+    pub fn new(interval: Duration, expires_at: DateTime<Utc>) -> Self {
+        DeviceAccessTokenPollState {
+            interval,
+            expires_at,
+        }
+    }
+
+    ///
+    /// Returns the interval to wait before the next poll.
+    ///
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    ///
+    /// Returns the time after which the flow should no longer be polled.
+    ///
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    ///
+    /// Returns whether the flow has expired at time `now`.
+    ///
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now > self.expires_at
+    }
+
+    ///
+    /// Returns the time remaining at time `now` until the flow expires, or zero if it has
+    /// already expired.
+    ///
+    pub fn time_remaining(&self, now: DateTime<Utc>) -> Duration {
+        self.expires_at
+            .signed_duration_since(now)
+            .to_std()
+            .unwrap_or_else(|_| Duration::from_secs(0))
+    }
+
+    ///
+    /// Processes the result of sending a device access token request, updating the polling
+    /// interval as required.
+    ///
+    /// Errors returned by the HTTP client are treated as transient: the interval is doubled and
+    /// [`DeviceAccessTokenPollStatus::TransientError`] is returned.
+    ///
+    pub fn process_response<TR, RE>(
+        &mut self,
+        response: Result<HttpResponse, RE>,
+    ) -> DeviceAccessTokenPollStatus<TR, RE>
+    where
+        TR: DeserializeOwned,
+        RE: Error + 'static,
+    {
+        let http_response = match response {
+            Ok(http_response) => http_response,
+            Err(err) => {
+                // Try and double the current interval. If that fails, just use the current one.
+                self.interval = self.interval.checked_mul(2).unwrap_or(self.interval);
+                return DeviceAccessTokenPollStatus::TransientError(err, self.interval);
+            }
+        };
+
+        // Explicitly process the response with a DeviceCodeErrorResponse
+        match endpoint_response::<RE, DeviceCodeErrorResponse, TR>(http_response) {
+            Ok(token) => DeviceAccessTokenPollStatus::Done(token),
+            // On a ServerResponse error, the error needs inspecting as a DeviceCodeErrorResponse
+            // to work out whether a retry needs to happen.
+            Err(RequestTokenError::ServerResponse(dcer)) => match dcer.error() {
+                // On AuthorizationPending, a retry needs to happen with the same poll interval.
+                DeviceCodeErrorResponseType::AuthorizationPending => {
+                    DeviceAccessTokenPollStatus::Pending(self.interval)
+                }
+                // On SlowDown, a retry needs to happen with a larger poll interval.
+                DeviceCodeErrorResponseType::SlowDown => {
+                    self.interval += Duration::from_secs(5);
+                    DeviceAccessTokenPollStatus::SlowDown(self.interval)
+                }
+                // On any other error, just return the error.
+                _ => DeviceAccessTokenPollStatus::Failed(
+                    RequestTokenError::ServerResponse(dcer).into(),
+                ),
+            },
+            // On any other failure, return the failure.
+            Err(err) => DeviceAccessTokenPollStatus::Failed(err.into()),
+        }
+    }
 }
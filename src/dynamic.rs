@@ -0,0 +1,256 @@
+use std::error::Error;
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::sync::Arc;
+
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::basic::{BasicErrorResponse, BasicRevocationErrorResponse, BasicTokenType};
+use crate::revocation::StandardRevocableToken;
+use crate::{
+    AccessToken, AuthorizationCode, AuthorizationRequest, Client, ConfigurationError, CsrfToken,
+    ExtraTokenFields, HttpRequest, HttpResponse, PkceCodeVerifier, RefreshToken, RequestTokenError,
+    Scope, StandardTokenIntrospectionResponse, StandardTokenResponse,
+};
+
+///
+/// Extra fields of a token or introspection response, represented as a JSON object.
+///
+/// Contains every field of the response other than those defined by the standard response type.
+///
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct JsonExtraFields(Map<String, Value>);
+impl JsonExtraFields {
+    ///
+    /// Returns the value of the extra field with the given name, if present.
+    ///
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.0.get(name)
+    }
+
+    ///
+    /// Returns all of the extra fields.
+    ///
+    pub fn as_map(&self) -> &Map<String, Value> {
+        &self.0
+    }
+}
+impl ExtraTokenFields for JsonExtraFields {}
+
+///
+/// Token response with extra fields represented as JSON.
+///
+pub type DynTokenResponse = StandardTokenResponse<JsonExtraFields, BasicTokenType>;
+
+///
+/// Token introspection response with extra fields represented as JSON.
+///
+pub type DynTokenIntrospectionResponse =
+    StandardTokenIntrospectionResponse<JsonExtraFields, BasicTokenType>;
+
+///
+/// [`Client`] specialization whose response types accept any provider's responses, as used by
+/// [`DynClient`].
+///
+pub type JsonClient = Client<
+    BasicErrorResponse,
+    DynTokenResponse,
+    BasicTokenType,
+    DynTokenIntrospectionResponse,
+    StandardRevocableToken,
+    BasicRevocationErrorResponse,
+>;
+
+///
+/// Type-erased error returned by the HTTP client of a [`DynClient`].
+///
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct DynHttpClientError(Box<dyn Error + Send + Sync>);
+
+///
+/// Error returned by [`DynClient`] token and introspection requests.
+///
+pub type DynRequestTokenError = RequestTokenError<DynHttpClientError, BasicErrorResponse>;
+
+///
+/// Error returned by [`DynClient`] revocation requests.
+///
+pub type DynRevocationError = RequestTokenError<DynHttpClientError, BasicRevocationErrorResponse>;
+
+type DynHttpClient = dyn Fn(HttpRequest) -> BoxFuture<'static, Result<HttpResponse, DynHttpClientError>>
+    + Send
+    + Sync;
+
+///
+/// OAuth2 client with no type parameters, suitable for applications that use several providers
+/// (e.g., storing clients for multiple providers in a `HashMap`).
+///
+/// Responses are parsed into the standard response types, with any provider-specific fields
+/// available as [`JsonExtraFields`]. Each client owns an asynchronous HTTP client, and the methods
+/// that contact the authorization server return boxed futures. Requests requiring options not
+/// exposed here may be built using the underlying [`client`](Self::client).
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::collections::HashMap;
+///
+/// use oauth2::dynamic::{DynClient, JsonClient};
+/// use oauth2::reqwest::async_http_client;
+/// use oauth2::{AuthUrl, ClientId, ClientSecret, Scope, TokenUrl};
+///
+/// # async fn err_wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut clients = HashMap::new();
+/// clients.insert(
+///     "example",
+///     DynClient::new(
+///         JsonClient::new(
+///             ClientId::new("client_id".to_string()),
+///             Some(ClientSecret::new("client_secret".to_string())),
+///             AuthUrl::new("https://example.com/auth".to_string())?,
+///             Some(TokenUrl::new("https://example.com/token".to_string())?),
+///         ),
+///         async_http_client,
+///     ),
+/// );
+///
+/// let token_response = clients["example"]
+///     .exchange_client_credentials(vec![Scope::new("read".to_string())])
+///     .await?;
+/// let id_token = token_response.extra_fields().get("id_token");
+/// # Ok(())
+/// # }
+/// ```
+///
+#[derive(Clone)]
+pub struct DynClient {
+    client: Arc<JsonClient>,
+    http_client: Arc<DynHttpClient>,
+}
+impl DynClient {
+    ///
+    /// Creates a new client that sends requests using `http_client`.
+    ///
+    pub fn new<C, F, RE>(client: JsonClient, http_client: C) -> Self
+    where
+        C: Fn(HttpRequest) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<HttpResponse, RE>> + Send + 'static,
+        RE: Error + Send + Sync + 'static,
+    {
+        let http_client = Arc::new(move |request| {
+            let response = http_client(request);
+            Box::pin(async move {
+                response
+                    .await
+                    .map_err(|err| DynHttpClientError(Box::new(err)))
+            }) as BoxFuture<'static, _>
+        });
+        DynClient {
+            client: Arc::new(client),
+            http_client,
+        }
+    }
+
+    ///
+    /// Returns the underlying client.
+    ///
+    pub fn client(&self) -> &JsonClient {
+        &self.client
+    }
+
+    ///
+    /// Generates an authorization URL for a new authorization request (see
+    /// [`Client::authorize_url`]).
+    ///
+    pub fn authorize_url<S>(&self, state_fn: S) -> AuthorizationRequest<'_>
+    where
+        S: FnOnce() -> CsrfToken,
+    {
+        self.client.authorize_url(state_fn)
+    }
+
+    ///
+    /// Exchanges a code produced by a successful authorization process with an access token
+    /// (see [`Client::exchange_code`]).
+    ///
+    pub fn exchange_code(
+        &self,
+        code: AuthorizationCode,
+        pkce_verifier: Option<PkceCodeVerifier>,
+    ) -> BoxFuture<'_, Result<DynTokenResponse, DynRequestTokenError>> {
+        Box::pin(async move {
+            let mut request = self.client.exchange_code(code);
+            if let Some(pkce_verifier) = pkce_verifier {
+                request = request.set_pkce_verifier(pkce_verifier);
+            }
+            request.request_async(&*self.http_client).await
+        })
+    }
+
+    ///
+    /// Requests an access token for the *client credentials* grant type (see
+    /// [`Client::exchange_client_credentials`]).
+    ///
+    pub fn exchange_client_credentials(
+        &self,
+        scopes: Vec<Scope>,
+    ) -> BoxFuture<'_, Result<DynTokenResponse, DynRequestTokenError>> {
+        Box::pin(
+            self.client
+                .exchange_client_credentials()
+                .add_scopes(scopes)
+                .request_async(&*self.http_client),
+        )
+    }
+
+    ///
+    /// Exchanges a refresh token for an access token (see [`Client::exchange_refresh_token`]).
+    ///
+    pub fn exchange_refresh_token<'a>(
+        &'a self,
+        refresh_token: &'a RefreshToken,
+    ) -> BoxFuture<'a, Result<DynTokenResponse, DynRequestTokenError>> {
+        Box::pin(
+            self.client
+                .exchange_refresh_token(refresh_token)
+                .request_async(&*self.http_client),
+        )
+    }
+
+    ///
+    /// Queries the introspection endpoint to determine the set of metadata for a previously
+    /// received access token (see [`Client::introspect`]).
+    ///
+    pub fn introspect<'a>(
+        &'a self,
+        token: &'a AccessToken,
+    ) -> Result<
+        BoxFuture<'a, Result<DynTokenIntrospectionResponse, DynRequestTokenError>>,
+        ConfigurationError,
+    > {
+        let request = self.client.introspect(token)?;
+        Ok(Box::pin(request.request_async(&*self.http_client)))
+    }
+
+    ///
+    /// Attempts to revoke the given previously received token (see [`Client::revoke_token`]).
+    ///
+    pub fn revoke_token(
+        &self,
+        token: StandardRevocableToken,
+    ) -> Result<BoxFuture<'_, Result<(), DynRevocationError>>, ConfigurationError> {
+        let request = self.client.revoke_token(token)?;
+        Ok(Box::pin(request.request_async(&*self.http_client)))
+    }
+}
+impl Debug for DynClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynClient")
+            .field("client", &self.client)
+            .finish()
+    }
+}
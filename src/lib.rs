@@ -6,6 +6,7 @@
 //!
 //! # Contents
 //! * [Importing `oauth2`: selecting an HTTP client interface](#importing-oauth2-selecting-an-http-client-interface)
+//!   * [Optional features](#optional-features)
 //! * [Getting started: Authorization Code Grant w/ PKCE](#getting-started-authorization-code-grant-w-pkce)
 //!   * [Example: Synchronous (blocking) API](#example-synchronous-blocking-api)
 //!   * [Example: Async/Await API](#example-asyncawait-api)
@@ -41,16 +42,25 @@
 //!
 //!  * **[`curl`]**
 //!
-//!    The `curl` HTTP client only supports the synchronous HTTP client mode and can be enabled in
-//!    `Cargo.toml` via the `curl` feature flag.
+//!    The `curl` HTTP client supports both modes and can be enabled in `Cargo.toml` via the
+//!    `curl` feature flag. The async client drives all requests through a shared curl multi
+//!    handle on a single background thread, so it never blocks the executor. With libcurl 7.68.0
+//!    or later, enable the `curl-multi-wakeup` feature flag so that new requests start
+//!    immediately rather than within a second while other requests are in progress.
 //!
 //!    Synchronous client: [`curl::http_client`]
 //!
+//!    Async/await `futures` 0.3 client: [`curl::async_http_client`]
+//!
 //! * **[`ureq`]**
 //!
 //!    The `ureq` HTTP client is a simple HTTP client with minimal dependencies. It only supports
 //!    the synchronous HTTP client mode and can be enabled in `Cargo.toml` via the `ureq` feature flag.
 //!
+//!    Synchronous client: [`ureq::http_client`]
+//!
+//!    Synchronous client reusing a configured `ureq::Agent`: [`ureq::agent_http_client`]
+//!
 //!  * **Custom**
 //!
 //!    In addition to the clients above, users may define their own HTTP clients, which must accept
@@ -75,6 +85,24 @@
 //!      RE: std::error::Error + 'static
 //!    ```
 //!
+//! ## Optional features
+//!
+//! The following feature flags enable functionality beyond the HTTP clients described above:
+//!  * `device-code-stream`: a stream of events reporting the progress of the
+//!    [Device Code Flow](#device-code-flow) (see
+//!    [`DeviceAccessTokenRequest::request_stream`]).
+//!  * `dynamic`: an OAuth2 client without type parameters, for applications that use multiple
+//!    providers ([`dynamic`]).
+//!  * `jwt`: validation of JWT access tokens ([`jwt`]).
+//!  * `mock-server`: an in-process authorization server for tests ([`mockserver`]).
+//!  * `testing`: a mock HTTP client that replays recorded interactions ([`testing`]).
+//!  * `token-source`: access token sources that obtain and cache tokens ([`tokensource`]).
+//!  * `tower`: [Tower](https://crates.io/crates/tower) middleware that authorizes outgoing
+//!    requests ([`tower`]). Implies `token-source`.
+//!  * `reqwest-middleware`: equivalent middleware for
+//!    [`reqwest-middleware`](https://crates.io/crates/reqwest-middleware). Implies
+//!    `token-source`.
+//!
 //! # Getting started: Authorization Code Grant w/ PKCE
 //!
 //! This is the most common OAuth2 flow. PKCE is recommended whenever the OAuth2 client has no
@@ -432,18 +460,40 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::Duration;
 
+#[cfg(feature = "device-code-stream")]
+use futures_util::stream::Stream;
 use http::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE};
 use http::status::StatusCode;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use url::{form_urlencoded, Url};
 
+///
+/// Typed parameters for authorization requests defined by common extensions, such as
+/// [OpenID Connect](https://openid.net/specs/openid-connect-core-1_0.html#AuthRequest).
+///
+pub mod authorization;
+
 ///
 /// Basic OAuth2 implementation with no extensions
 /// ([RFC 6749](https://tools.ietf.org/html/rfc6749)).
 ///
 pub mod basic;
 
+///
+/// Helpers for sending access tokens to protected resources
+/// ([RFC 6750](https://tools.ietf.org/html/rfc6750)).
+///
+pub mod bearer;
+
+mod builder;
+
+///
+/// Providers of client secrets that are resolved for each request, supporting secret rotation.
+///
+pub mod credentials;
+use credentials::{ClientSecretSource, CredentialProvider};
+
 ///
 /// HTTP client backed by the [curl](https://crates.io/crates/curl) crate.
 /// Requires "curl" feature.
@@ -451,6 +501,13 @@ pub mod basic;
 #[cfg(all(feature = "curl", not(target_arch = "wasm32")))]
 pub mod curl;
 
+///
+/// OAuth2 client without type parameters, for applications that use multiple providers.
+/// Requires "dynamic" feature.
+///
+#[cfg(feature = "dynamic")]
+pub mod dynamic;
+
 #[cfg(all(feature = "curl", target_arch = "wasm32"))]
 compile_error!("wasm32 is not supported with the `curl` feature. Use the `reqwest` backend or a custom backend for wasm32 support");
 
@@ -459,11 +516,39 @@ compile_error!("wasm32 is not supported with the `curl` feature. Use the `reqwes
 /// ([RFC 8628](https://tools.ietf.org/html/rfc8628)).
 ///
 pub mod devicecode;
+#[cfg(feature = "device-code-stream")]
+use devicecode::DeviceAccessTokenEvent;
 use devicecode::{
-    DeviceAccessTokenPollResult, DeviceAuthorizationResponse, DeviceCodeErrorResponse,
-    DeviceCodeErrorResponseType, ExtraDeviceAuthorizationFields,
+    DeviceAccessTokenError, DeviceAccessTokenPollState, DeviceAccessTokenPollStatus,
+    DeviceAuthorizationResponse, DeviceCodeErrorResponse, ExtraDeviceAuthorizationFields,
+    StandardDeviceAuthorizationResponse,
 };
 
+///
+/// Local validation of JWT-formatted access tokens
+/// ([RFC 9068](https://tools.ietf.org/html/rfc9068)). Requires "jwt" feature.
+///
+#[cfg(feature = "jwt")]
+pub mod jwt;
+
+///
+/// In-process mock authorization server for end-to-end testing of OAuth2 flows.
+/// Requires "mock-server" feature.
+///
+#[cfg(all(feature = "mock-server", not(target_arch = "wasm32")))]
+pub mod mockserver;
+
+///
+/// Registry of clients for multiple tenants and providers, loaded from configuration files.
+///
+pub mod registry;
+
+///
+/// Resource server support for extracting bearer tokens from requests and rejecting them
+/// ([RFC 6750](https://tools.ietf.org/html/rfc6750)).
+///
+pub mod resource;
+
 ///
 /// OAuth 2.0 Token Revocation implementation
 /// ([RFC 7009](https://tools.ietf.org/html/rfc7009)).
@@ -482,9 +567,41 @@ pub mod helpers;
 #[cfg(feature = "reqwest")]
 pub mod reqwest;
 
+///
+/// Variant of [`Client`] that enforces the requirements of
+/// [OAuth 2.1](https://tools.ietf.org/html/draft-ietf-oauth-v2-1).
+///
+pub mod strict;
+
+///
+/// Record-and-replay HTTP clients for writing deterministic tests against recorded
+/// authorization server interactions. Requires "testing" feature.
+///
+#[cfg(feature = "testing")]
+pub mod testing;
+
 #[cfg(test)]
 mod tests;
 
+///
+/// Access token sources that obtain and cache tokens for authorizing requests to protected
+/// resources. Requires "token-source" feature.
+///
+#[cfg(feature = "token-source")]
+pub mod tokensource;
+
+///
+/// [Tower](https://crates.io/crates/tower) middleware for authorizing outgoing requests with
+/// access tokens. Requires "tower" feature.
+///
+#[cfg(feature = "tower")]
+pub mod tower;
+
+///
+/// Variant of [`Client`] that tracks which optional endpoints are configured at compile time.
+///
+pub mod typestate;
+
 mod types;
 
 ///
@@ -501,14 +618,18 @@ pub use http;
 pub use url;
 
 pub use types::{
-    AccessToken, AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken,
-    DeviceAuthorizationUrl, DeviceCode, EndUserVerificationUrl, IntrospectionUrl,
-    PkceCodeChallenge, PkceCodeChallengeMethod, PkceCodeVerifier, RedirectUrl, RefreshToken,
-    ResourceOwnerPassword, ResourceOwnerUsername, ResponseType, RevocationUrl, Scope, TokenUrl,
-    UserCode,
+    AccessToken, AuthUrl, AuthenticationContextClass, AuthorizationCode, ClientId, ClientSecret,
+    CsrfToken, DeviceAuthorizationUrl, DeviceCode, EndUserVerificationUrl, IntrospectionUrl,
+    JsonWebKeySetUrl, LanguageTag, LoginHint, Nonce, PkceCodeChallenge, PkceCodeChallengeMethod,
+    PkceCodeVerifier, RedirectUrl, RefreshToken, ResourceOwnerPassword, ResourceOwnerUsername,
+    ResponseType, RevocationUrl, Scope, TokenUrl, UserCode,
 };
 
-pub use revocation::{RevocableToken, RevocationErrorResponseType, StandardRevocableToken};
+pub use builder::{ClientBuilder, GrantType};
+
+pub use revocation::{
+    RevocableToken, RevocationErrorResponse, RevocationErrorResponseType, StandardRevocableToken,
+};
 
 const CONTENT_TYPE_JSON: &str = "application/json";
 const CONTENT_TYPE_FORMENCODED: &str = "application/x-www-form-urlencoded";
@@ -517,7 +638,7 @@ const CONTENT_TYPE_FORMENCODED: &str = "application/x-www-form-urlencoded";
 /// There was a problem configuring the request.
 ///
 #[non_exhaustive]
-#[derive(Debug, thiserror::Error)]
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
 pub enum ConfigurationError {
     ///
     /// The endpoint URL tp be contacted is missing.
@@ -529,6 +650,95 @@ pub enum ConfigurationError {
     ///
     #[error("Scheme for {0} endpoint URL must be HTTPS")]
     InsecureUrl(&'static str),
+    ///
+    /// The requested flow is not permitted in strict mode (see
+    /// [`Client::set_strict_mode`]).
+    ///
+    #[error("The {0} flow is not permitted by OAuth 2.1")]
+    UnsupportedFlow(&'static str),
+    ///
+    /// The authorization request does not include a PKCE code challenge using the `S256`
+    /// method, which is required in strict mode (see [`Client::set_strict_mode`]).
+    ///
+    #[error("PKCE code challenge using the S256 method is required by OAuth 2.1")]
+    PkceChallengeRequired,
+    ///
+    /// The authorization code token request does not include a PKCE code verifier.
+    ///
+    #[error("PKCE code verifier is required")]
+    PkceVerifierRequired,
+    ///
+    /// The PKCE code verifier does not match the code challenge sent in the authorization
+    /// request.
+    ///
+    #[error("PKCE code verifier does not match the code challenge")]
+    PkceVerifierMismatch,
+    ///
+    /// HTTP Basic authentication was requested (see [`AuthType::BasicAuth`]) but no client
+    /// secret was provided.
+    ///
+    #[error("Client secret is required for HTTP Basic authentication")]
+    MissingClientSecret,
+    ///
+    /// The redirect URL is not valid for the reason included.
+    ///
+    #[error("Invalid redirect URL: {0}")]
+    InvalidRedirectUrl(&'static str),
+    ///
+    /// The named authorization request parameter is not valid for the reason included.
+    ///
+    #[error("Invalid {0} parameter: {1}")]
+    InvalidParameter(&'static str, &'static str),
+}
+
+///
+/// Checks that `url` uses HTTPS, or plain HTTP to a loopback address (e.g., for native apps
+/// as described in [RFC 8252](https://tools.ietf.org/html/rfc8252#section-7.3)).
+///
+fn check_secure_url(url: &Url, endpoint: &'static str) -> Result<(), ConfigurationError> {
+    let loopback = match url.host() {
+        Some(url::Host::Domain(domain)) => domain == "localhost",
+        Some(url::Host::Ipv4(addr)) => addr.is_loopback(),
+        Some(url::Host::Ipv6(addr)) => addr.is_loopback(),
+        None => false,
+    };
+    match url.scheme() {
+        "https" => Ok(()),
+        "http" if loopback => Ok(()),
+        _ => Err(ConfigurationError::InsecureUrl(endpoint)),
+    }
+}
+
+///
+/// Converts a strict mode violation detected while sending a request into a
+/// [`RequestTokenError`], so that the request fails without being sent.
+///
+fn strict_mode_error<RE, TE>(err: ConfigurationError) -> RequestTokenError<RE, TE>
+where
+    RE: Error + 'static,
+    TE: ErrorResponse + 'static,
+{
+    RequestTokenError::Other(err.to_string())
+}
+
+///
+/// Returns the token endpoint URL, enforcing the strict mode requirements if enabled.
+///
+fn token_endpoint_url<RE, TE>(
+    token_url: Option<&TokenUrl>,
+    strict: bool,
+) -> Result<&Url, RequestTokenError<RE, TE>>
+where
+    RE: Error + 'static,
+    TE: ErrorResponse + 'static,
+{
+    let token_url = token_url
+        .ok_or_else(|| RequestTokenError::Other("no token_url provided".to_string()))?
+        .url();
+    if strict {
+        check_secure_url(token_url, "token").map_err(strict_mode_error)?;
+    }
+    Ok(token_url)
 }
 
 ///
@@ -618,6 +828,7 @@ where
 {
     client_id: ClientId,
     client_secret: Option<ClientSecret>,
+    credential_provider: Option<Arc<dyn CredentialProvider>>,
     auth_url: AuthUrl,
     auth_type: AuthType,
     token_url: Option<TokenUrl>,
@@ -625,6 +836,7 @@ where
     introspection_url: Option<IntrospectionUrl>,
     revocation_url: Option<RevocationUrl>,
     device_authorization_url: Option<DeviceAuthorizationUrl>,
+    strict: bool,
     phantom: PhantomData<(TE, TR, TT, TIR, RT, TRE)>,
 }
 
@@ -657,6 +869,9 @@ where
     ///   [Implicit Grant](https://tools.ietf.org/html/rfc6749#section-4.2). If this value is set
     ///   to `None`, the `exchange_*` methods will return `Err(RequestTokenError::Other(_))`.
     ///
+    /// This constructor does not validate the configuration. Use [`ClientBuilder`] to detect
+    /// configuration errors before making any requests.
+    ///
     pub fn new(
         client_id: ClientId,
         client_secret: Option<ClientSecret>,
@@ -666,6 +881,7 @@ where
         Client {
             client_id,
             client_secret,
+            credential_provider: None,
             auth_url,
             auth_type: AuthType::BasicAuth,
             token_url,
@@ -673,6 +889,7 @@ where
             introspection_url: None,
             revocation_url: None,
             device_authorization_url: None,
+            strict: false,
             phantom: PhantomData,
         }
     }
@@ -690,6 +907,22 @@ where
         self
     }
 
+    ///
+    /// Configures a provider that supplies the client secret for each request, overriding the
+    /// client secret passed to [`new`](Self::new).
+    ///
+    /// If the authorization server responds to a request with an `invalid_client` error, the
+    /// provider is notified (see [`CredentialProvider::invalid_client`]) so that it may switch to
+    /// a different secret (e.g., a secondary secret during a rotation window). The failed request
+    /// still returns the error, except while polling for a device access token, which retries
+    /// immediately with the new secret.
+    ///
+    pub fn set_credential_provider(mut self, provider: Arc<dyn CredentialProvider>) -> Self {
+        self.credential_provider = Some(provider);
+
+        self
+    }
+
     ///
     /// Sets the the redirect URL used by the authorization endpoint.
     ///
@@ -733,6 +966,23 @@ where
         self
     }
 
+    ///
+    /// Enables strict mode, which enforces the requirements of
+    /// [OAuth 2.1](https://tools.ietf.org/html/draft-ietf-oauth-v2-1) and the
+    /// [OAuth 2.0 Security Best Current Practice](https://tools.ietf.org/html/draft-ietf-oauth-security-topics).
+    ///
+    /// Strict mode is disabled by default. The returned [`StrictClient`](strict::StrictClient)
+    /// only builds authorization URLs using
+    /// [`try_url`](AuthorizationRequest::<strict::Strict>::try_url), and does not support the
+    /// grants removed by OAuth 2.1. See [`StrictClient`](strict::StrictClient) for the
+    /// requirements it enforces.
+    ///
+    pub fn set_strict_mode(mut self) -> strict::StrictClient<TE, TR, TT, TIR, RT, TRE> {
+        self.strict = true;
+
+        strict::StrictClient::new(self)
+    }
+
     ///
     /// Generates an authorization URL for a new authorization request.
     ///
@@ -752,18 +1002,42 @@ where
     ///  instead.
     ///
     pub fn authorize_url<S>(&self, state_fn: S) -> AuthorizationRequest
+    where
+        S: FnOnce() -> CsrfToken,
+    {
+        self.authorization_request(state_fn)
+    }
+
+    fn authorization_request<S, M>(&self, state_fn: S) -> AuthorizationRequest<'_, M>
     where
         S: FnOnce() -> CsrfToken,
     {
         AuthorizationRequest {
+            acr_values: Vec::new(),
             auth_url: &self.auth_url,
+            claims: None,
             client_id: &self.client_id,
+            display: None,
             extra_params: Vec::new(),
+            login_hint: None,
+            max_age: None,
+            nonce: None,
             pkce_challenge: None,
+            prompts: Vec::new(),
             redirect_url: self.redirect_url.as_ref().map(Cow::Borrowed),
+            response_mode: None,
             response_type: "code".into(),
             scopes: Vec::new(),
             state: state_fn(),
+            ui_locales: Vec::new(),
+            _mode: PhantomData,
+        }
+    }
+
+    fn client_secret_source(&self) -> ClientSecretSource<'_> {
+        ClientSecretSource {
+            client_secret: self.client_secret.as_ref(),
+            provider: self.credential_provider.as_deref(),
         }
     }
 
@@ -779,10 +1053,12 @@ where
         CodeTokenRequest {
             auth_type: &self.auth_type,
             client_id: &self.client_id,
-            client_secret: self.client_secret.as_ref(),
+            client_secret: self.client_secret_source(),
             code,
+            expected_pkce_challenge: None,
             extra_params: Vec::new(),
             pkce_verifier: None,
+            strict: self.strict,
             token_url: self.token_url.as_ref(),
             redirect_url: self.redirect_url.as_ref().map(Cow::Borrowed),
             _phantom: PhantomData,
@@ -794,6 +1070,9 @@ where
     ///
     /// See https://tools.ietf.org/html/rfc6749#section-4.3.2
     ///
+    /// The password grant is not supported in strict mode (see
+    /// [`set_strict_mode`](Self::set_strict_mode)).
+    ///
     pub fn exchange_password<'a, 'b>(
         &'a self,
         username: &'b ResourceOwnerUsername,
@@ -805,7 +1084,7 @@ where
         PasswordTokenRequest::<'b> {
             auth_type: &self.auth_type,
             client_id: &self.client_id,
-            client_secret: self.client_secret.as_ref(),
+            client_secret: self.client_secret_source(),
             username,
             password,
             extra_params: Vec::new(),
@@ -824,9 +1103,10 @@ where
         ClientCredentialsTokenRequest {
             auth_type: &self.auth_type,
             client_id: &self.client_id,
-            client_secret: self.client_secret.as_ref(),
+            client_secret: self.client_secret_source(),
             extra_params: Vec::new(),
             scopes: Vec::new(),
+            strict: self.strict,
             token_url: self.token_url.as_ref(),
             _phantom: PhantomData,
         }
@@ -847,10 +1127,11 @@ where
         RefreshTokenRequest {
             auth_type: &self.auth_type,
             client_id: &self.client_id,
-            client_secret: self.client_secret.as_ref(),
+            client_secret: self.client_secret_source(),
             extra_params: Vec::new(),
             refresh_token,
             scopes: Vec::new(),
+            strict: self.strict,
             token_url: self.token_url.as_ref(),
             _phantom: PhantomData,
         }
@@ -863,16 +1144,22 @@ where
     pub fn exchange_device_code(
         &self,
     ) -> Result<DeviceAuthorizationRequest<TE>, ConfigurationError> {
+        let device_authorization_url = self
+            .device_authorization_url
+            .as_ref()
+            .ok_or(ConfigurationError::MissingUrl("device authorization_url"))?;
+        if self.strict {
+            check_secure_url(device_authorization_url.url(), "device authorization")?;
+        }
+
         Ok(DeviceAuthorizationRequest {
             auth_type: &self.auth_type,
             client_id: &self.client_id,
-            client_secret: self.client_secret.as_ref(),
+            client_secret: self.client_secret_source(),
             extra_params: Vec::new(),
             scopes: Vec::new(),
-            device_authorization_url: self
-                .device_authorization_url
-                .as_ref()
-                .ok_or(ConfigurationError::MissingUrl("device authorization_url"))?,
+            device_authorization_url,
+            time_fn: Arc::new(Utc::now),
             _phantom: PhantomData,
         })
     }
@@ -892,8 +1179,9 @@ where
         DeviceAccessTokenRequest {
             auth_type: &self.auth_type,
             client_id: &self.client_id,
-            client_secret: self.client_secret.as_ref(),
+            client_secret: self.client_secret_source(),
             extra_params: Vec::new(),
+            strict: self.strict,
             token_url: self.token_url.as_ref(),
             dev_auth_resp: auth_response,
             time_fn: Arc::new(Utc::now),
@@ -911,19 +1199,32 @@ where
     /// Attempting to submit the generated request without calling [`set_introspection_uri()`](Self::set_introspection_uri())
     /// first will result in an error.
     ///
-    pub fn introspect<'a>(
+    /// The `token_type_hint` parameter is only sent if it is set using
+    /// [`IntrospectionRequest::set_token_type_hint`] or provided by the type of `token` (see
+    /// [`IntrospectableToken::type_hint`]). Refresh tokens are sent with a `refresh_token` hint,
+    /// while access tokens are sent without a hint.
+    ///
+    pub fn introspect<'a, T>(
         &'a self,
-        token: &'a AccessToken,
-    ) -> Result<IntrospectionRequest<'a, TE, TIR, TT>, ConfigurationError> {
+        token: &'a T,
+    ) -> Result<IntrospectionRequest<'a, TE, TIR, TT>, ConfigurationError>
+    where
+        T: IntrospectableToken,
+    {
+        let introspection_url = self
+            .introspection_url
+            .as_ref()
+            .ok_or(ConfigurationError::MissingUrl("introspection"))?;
+        if self.strict {
+            check_secure_url(introspection_url.url(), "introspection")?;
+        }
+
         Ok(IntrospectionRequest {
             auth_type: &self.auth_type,
             client_id: &self.client_id,
-            client_secret: self.client_secret.as_ref(),
+            client_secret: self.client_secret_source(),
             extra_params: Vec::new(),
-            introspection_url: self
-                .introspection_url
-                .as_ref()
-                .ok_or(ConfigurationError::MissingUrl("introspection"))?,
+            introspection_url,
             token,
             token_type_hint: None,
             _phantom: PhantomData,
@@ -935,7 +1236,7 @@ where
     /// compatible endpoint.
     ///
     /// Requires that [`set_revocation_uri()`](Self::set_revocation_uri()) have already been called to set the
-    /// revocation endpoint URL.
+    /// revocation endpoint URL, which must use HTTPS (or plain HTTP to a loopback address).
     ///
     /// Attempting to submit the generated request without calling [`set_revocation_uri()`](Self::set_revocation_uri())
     /// first will result in an error.
@@ -949,39 +1250,87 @@ where
         //    HTTP POST request to the token revocation endpoint URL.  This URL
         //    MUST conform to the rules given in [RFC6749], Section 3.1.  Clients
         //    MUST verify that the URL is an HTTPS URL."
-        let revocation_url = match self.revocation_url.as_ref() {
-            Some(url) if url.url().scheme() == "https" => Ok(url),
-            Some(_) => Err(ConfigurationError::InsecureUrl("revocation")),
-            None => Err(ConfigurationError::MissingUrl("revocation")),
-        }?;
+        // Plain HTTP to a loopback address is also accepted, since the request never leaves the
+        // host (e.g., when testing against a local server).
+        let revocation_url = self
+            .revocation_url
+            .as_ref()
+            .ok_or(ConfigurationError::MissingUrl("revocation"))?;
+        check_secure_url(revocation_url.url(), "revocation")?;
 
         Ok(RevocationRequest {
             auth_type: &self.auth_type,
             client_id: &self.client_id,
-            client_secret: self.client_secret.as_ref(),
+            client_secret: self.client_secret_source(),
             extra_params: Vec::new(),
             revocation_url,
             token,
             _phantom: PhantomData,
         })
     }
+
+    ///
+    /// Attempts to revoke the tokens in `token_response` using an
+    /// [RFC 7009 OAuth 2.0 Token Revocation](https://tools.ietf.org/html/rfc7009) compatible
+    /// endpoint (e.g., when a user logs out or deletes their account).
+    ///
+    /// The refresh token (if any) is revoked first, since revoking it should also invalidate the
+    /// access tokens issued with it. The access token is also revoked if `token_response` does
+    /// not include a refresh token, or if the server responds with an `unsupported_token_type`
+    /// error indicating that it does not support revoking refresh tokens.
+    ///
+    /// Requires that [`set_revocation_uri()`](Self::set_revocation_uri()) have already been
+    /// called to set the revocation endpoint URL, which must use HTTPS (or plain HTTP to a
+    /// loopback address).
+    ///
+    pub fn revoke_token_response(
+        &self,
+        token_response: &TR,
+    ) -> Result<TokenResponseRevocationRequest<'_, RT, TRE>, ConfigurationError>
+    where
+        RT: From<AccessToken> + From<RefreshToken>,
+        TRE: RevocationErrorResponse,
+    {
+        let refresh_token = match token_response.refresh_token() {
+            Some(refresh_token) => Some(self.revoke_token(refresh_token.clone().into())?),
+            None => None,
+        };
+        Ok(TokenResponseRevocationRequest {
+            refresh_token,
+            access_token: self.revoke_token(token_response.access_token().clone().into())?,
+        })
+    }
 }
 
 ///
 /// A request to the authorization endpoint
 ///
+/// Requests created by a [`StrictClient`](strict::StrictClient) have the mode `M` set to
+/// [`Strict`](strict::Strict), and can only be turned into an authorization URL using
+/// [`try_url`](AuthorizationRequest::<strict::Strict>::try_url), which enforces strict mode.
+///
 #[derive(Debug)]
-pub struct AuthorizationRequest<'a> {
+pub struct AuthorizationRequest<'a, M = strict::NonStrict> {
+    acr_values: Vec<AuthenticationContextClass>,
     auth_url: &'a AuthUrl,
+    claims: Option<authorization::ClaimsRequest>,
     client_id: &'a ClientId,
+    display: Option<authorization::AuthDisplay>,
     extra_params: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+    login_hint: Option<LoginHint>,
+    max_age: Option<Duration>,
+    nonce: Option<Nonce>,
     pkce_challenge: Option<PkceCodeChallenge>,
+    prompts: Vec<authorization::Prompt>,
     redirect_url: Option<Cow<'a, RedirectUrl>>,
+    response_mode: Option<authorization::ResponseMode>,
     response_type: Cow<'a, str>,
     scopes: Vec<Cow<'a, Scope>>,
     state: CsrfToken,
+    ui_locales: Vec<LanguageTag>,
+    _mode: PhantomData<M>,
 }
-impl<'a> AuthorizationRequest<'a> {
+impl<'a, M> AuthorizationRequest<'a, M> {
     ///
     /// Appends a new scope to the authorization URL.
     ///
@@ -1025,14 +1374,6 @@ impl<'a> AuthorizationRequest<'a> {
         self
     }
 
-    ///
-    /// Enables the [Implicit Grant](https://tools.ietf.org/html/rfc6749#section-4.2) flow.
-    ///
-    pub fn use_implicit_flow(mut self) -> Self {
-        self.response_type = "token".into();
-        self
-    }
-
     ///
     /// Enables custom flows other than the `code` and `token` (implicit flow) grant.
     ///
@@ -1063,16 +1404,177 @@ impl<'a> AuthorizationRequest<'a> {
     }
 
     ///
-    /// Returns the full authorization URL and CSRF state for this authorization
-    /// request.
+    /// Sets the mechanism the authorization server should use for returning parameters to the
+    /// redirect URI (the `response_mode` parameter).
     ///
-    pub fn url(self) -> (Url, CsrfToken) {
+    pub fn set_response_mode(mut self, response_mode: authorization::ResponseMode) -> Self {
+        self.response_mode = Some(response_mode);
+        self
+    }
+
+    ///
+    /// Appends a value to the `prompt` parameter, which specifies whether the authorization
+    /// server should prompt the end-user for reauthentication and consent.
+    ///
+    /// [`Prompt::None`](authorization::Prompt::None) must not be combined with any other value,
+    /// and extension values must not be empty or contain whitespace. These requirements are
+    /// enforced by [`try_url`](Self::try_url).
+    ///
+    pub fn add_prompt(mut self, prompt: authorization::Prompt) -> Self {
+        if !self.prompts.contains(&prompt) {
+            self.prompts.push(prompt);
+        }
+        self
+    }
+
+    ///
+    /// Sets how the authorization server should display its user interface (the `display`
+    /// parameter).
+    ///
+    pub fn set_display(mut self, display: authorization::AuthDisplay) -> Self {
+        self.display = Some(display);
+        self
+    }
+
+    ///
+    /// Sets a hint about the login identifier the end-user might use to log in (the
+    /// `login_hint` parameter).
+    ///
+    /// The login hint must not be empty, which is enforced by [`try_url`](Self::try_url).
+    ///
+    pub fn set_login_hint(mut self, login_hint: LoginHint) -> Self {
+        self.login_hint = Some(login_hint);
+        self
+    }
+
+    ///
+    /// Sets the maximum time since the end-user last actively authenticated, after which the
+    /// authorization server must reauthenticate the end-user (the `max_age` parameter).
+    ///
+    /// The duration is rounded down to whole seconds.
+    ///
+    pub fn set_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    ///
+    /// Appends a preferred language for the authorization server's user interface, in order
+    /// of preference (the `ui_locales` parameter).
+    ///
+    /// Each value must consist only of ASCII letters, digits and hyphens (e.g., `en-US`), which
+    /// is enforced by [`try_url`](Self::try_url).
+    ///
+    pub fn add_ui_locale(mut self, ui_locale: LanguageTag) -> Self {
+        self.ui_locales.push(ui_locale);
+        self
+    }
+
+    ///
+    /// Appends a requested Authentication Context Class Reference value, in order of
+    /// preference (the `acr_values` parameter).
+    ///
+    /// Each value must not be empty or contain whitespace, which is enforced by
+    /// [`try_url`](Self::try_url).
+    ///
+    pub fn add_auth_context_value(mut self, acr_value: AuthenticationContextClass) -> Self {
+        self.acr_values.push(acr_value);
+        self
+    }
+
+    ///
+    /// Sets the `nonce` parameter, which associates the client session with the resulting ID
+    /// token (see [`Nonce::new_random`]). The nonce must be retained by the client in order to
+    /// verify the ID token.
+    ///
+    pub fn set_nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    ///
+    /// Requests specific claims to be returned from the UserInfo endpoint and/or in the ID token
+    /// (the `claims` parameter).
+    ///
+    pub fn set_claims(mut self, claims: authorization::ClaimsRequest) -> Self {
+        self.claims = Some(claims);
+        self
+    }
+
+    fn check_params(&self) -> Result<(), ConfigurationError> {
+        // `prompt`, `ui_locales` and `acr_values` are space-delimited lists.
+        let is_list_value = |value: &str| !value.is_empty() && !value.contains(char::is_whitespace);
+
+        if self.prompts.len() > 1 && self.prompts.contains(&authorization::Prompt::None) {
+            return Err(ConfigurationError::InvalidParameter(
+                "prompt",
+                "`none` must not be combined with other values",
+            ));
+        }
+        if !self
+            .prompts
+            .iter()
+            .all(|prompt| is_list_value(prompt.as_ref()))
+        {
+            return Err(ConfigurationError::InvalidParameter(
+                "prompt",
+                "values must not be empty or contain whitespace",
+            ));
+        }
+        let is_language_tag = |tag: &LanguageTag| {
+            !tag.is_empty() && tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        };
+        if !self.ui_locales.iter().all(is_language_tag) {
+            return Err(ConfigurationError::InvalidParameter(
+                "ui_locales",
+                "values must be BCP 47 language tags",
+            ));
+        }
+        if !self.acr_values.iter().all(|acr| is_list_value(acr)) {
+            return Err(ConfigurationError::InvalidParameter(
+                "acr_values",
+                "values must not be empty or contain whitespace",
+            ));
+        }
+        match self.login_hint {
+            Some(ref login_hint) if login_hint.is_empty() => Err(
+                ConfigurationError::InvalidParameter("login_hint", "value must not be empty"),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_strict(&self) -> Result<(), ConfigurationError> {
+        if self.response_type.split(' ').any(|value| value == "token") {
+            return Err(ConfigurationError::UnsupportedFlow("implicit"));
+        }
+        match self.pkce_challenge {
+            Some(ref pkce_challenge) if pkce_challenge.method().as_str() == "S256" => {}
+            _ => return Err(ConfigurationError::PkceChallengeRequired),
+        }
+        check_secure_url(self.auth_url.url(), "authorization")?;
+        if let Some(ref redirect_url) = self.redirect_url {
+            // Private-use URI schemes are permitted for native apps (see
+            // https://tools.ietf.org/html/rfc8252#section-7.1).
+            if redirect_url.url().scheme() == "http" {
+                check_secure_url(redirect_url.url(), "redirect")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn build_url(self) -> (Url, CsrfToken) {
         let scopes = self
             .scopes
             .iter()
             .map(|s| s.to_string())
             .collect::<Vec<_>>()
             .join(" ");
+        let prompts = join_space_delimited(self.prompts.iter().map(AsRef::as_ref));
+        let ui_locales = join_space_delimited(self.ui_locales.iter().map(|tag| tag.as_str()));
+        let acr_values = join_space_delimited(self.acr_values.iter().map(|acr| acr.as_str()));
+        let max_age = self.max_age.map(|max_age| max_age.as_secs().to_string());
+        let claims = self.claims.as_ref().map(ToString::to_string);
 
         let url = {
             let mut pairs: Vec<(&str, &str)> = vec![
@@ -1094,6 +1596,42 @@ impl<'a> AuthorizationRequest<'a> {
                 pairs.push(("scope", &scopes));
             }
 
+            if let Some(ref response_mode) = self.response_mode {
+                pairs.push(("response_mode", response_mode.as_ref()));
+            }
+
+            if let Some(ref nonce) = self.nonce {
+                pairs.push(("nonce", nonce.secret()));
+            }
+
+            if !prompts.is_empty() {
+                pairs.push(("prompt", &prompts));
+            }
+
+            if let Some(ref display) = self.display {
+                pairs.push(("display", display.as_ref()));
+            }
+
+            if let Some(ref max_age) = max_age {
+                pairs.push(("max_age", max_age));
+            }
+
+            if !ui_locales.is_empty() {
+                pairs.push(("ui_locales", &ui_locales));
+            }
+
+            if !acr_values.is_empty() {
+                pairs.push(("acr_values", &acr_values));
+            }
+
+            if let Some(ref login_hint) = self.login_hint {
+                pairs.push(("login_hint", login_hint));
+            }
+
+            if let Some(ref claims) = claims {
+                pairs.push(("claims", claims));
+            }
+
             let mut url: Url = self.auth_url.url().to_owned();
 
             url.query_pairs_mut()
@@ -1107,17 +1645,69 @@ impl<'a> AuthorizationRequest<'a> {
         (url, self.state)
     }
 }
+impl<'a> AuthorizationRequest<'a> {
+    ///
+    /// Enables the [Implicit Grant](https://tools.ietf.org/html/rfc6749#section-4.2) flow.
+    ///
+    /// The implicit flow is not supported in strict mode (see [`Client::set_strict_mode`]).
+    ///
+    pub fn use_implicit_flow(mut self) -> Self {
+        self.response_type = "token".into();
+        self
+    }
 
-///
-/// An HTTP request.
-///
-#[derive(Clone, Debug)]
-pub struct HttpRequest {
-    // These are all owned values so that the request can safely be passed between
-    // threads.
-    /// URL to which the HTTP request is being made.
-    pub url: Url,
-    /// HTTP request method for this request.
+    ///
+    /// Returns the full authorization URL and CSRF state for this authorization
+    /// request, or an error if any of the parameters is invalid (see
+    /// [`ConfigurationError::InvalidParameter`]).
+    ///
+    pub fn try_url(self) -> Result<(Url, CsrfToken), ConfigurationError> {
+        self.check_params()?;
+        Ok(self.build_url())
+    }
+
+    ///
+    /// Returns the full authorization URL and CSRF state for this authorization
+    /// request.
+    ///
+    /// This method does not validate the parameters. Use [`try_url`](Self::try_url) to reject
+    /// invalid requests.
+    ///
+    pub fn url(self) -> (Url, CsrfToken) {
+        self.build_url()
+    }
+}
+impl<'a> AuthorizationRequest<'a, strict::Strict> {
+    ///
+    /// Returns the full authorization URL and CSRF state for this authorization
+    /// request, or an error if any of the parameters is invalid (see
+    /// [`ConfigurationError::InvalidParameter`]) or the request violates the requirements of
+    /// strict mode (see [`StrictClient`](strict::StrictClient)).
+    ///
+    pub fn try_url(self) -> Result<(Url, CsrfToken), ConfigurationError> {
+        self.check_params()?;
+        self.check_strict()?;
+        Ok(self.build_url())
+    }
+}
+
+fn join_space_delimited<'a, I>(values: I) -> String
+where
+    I: Iterator<Item = &'a str>,
+{
+    values.collect::<Vec<_>>().join(" ")
+}
+
+///
+/// An HTTP request.
+///
+#[derive(Clone, Debug)]
+pub struct HttpRequest {
+    // These are all owned values so that the request can safely be passed between
+    // threads.
+    /// URL to which the HTTP request is being made.
+    pub url: Url,
+    /// HTTP request method for this request.
     pub method: http::method::Method,
     /// HTTP request headers to send.
     pub headers: HeaderMap,
@@ -1138,6 +1728,102 @@ pub struct HttpResponse {
     pub body: Vec<u8>,
 }
 
+///
+/// Request to an authorization server endpoint, decoupled from sending the HTTP request.
+///
+/// This trait supports driving OAuth2 flows using any I/O mechanism (e.g., a message queue or
+/// batched requests), and writing code that is generic over the supported requests. The
+/// `request` and `request_async` methods of each request type are equivalent to sending the
+/// request returned by [`prepare`](Self::prepare) and passing the response to
+/// [`parse`](Self::parse).
+///
+/// Unlike `request` and `request_async`, [`parse`](Self::parse) does not notify the client's
+/// [`CredentialProvider`] when the authorization server rejects the client secret, since the
+/// secret used for the request is not known. Callers using a credential provider should call
+/// [`CredentialProvider::invalid_client`] when receiving an `invalid_client` error.
+///
+/// # Example
+///
+/// ```rust
+/// use oauth2::basic::BasicClient;
+/// use oauth2::{
+///     AuthUrl, ClientId, ClientSecret, EndpointRequest, HttpResponse, TokenResponse, TokenUrl,
+/// };
+/// use oauth2::http::{header::CONTENT_TYPE, HeaderMap, HeaderValue, StatusCode};
+///
+/// # fn err_wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = BasicClient::new(
+///     ClientId::new("client_id".to_string()),
+///     Some(ClientSecret::new("client_secret".to_string())),
+///     AuthUrl::new("https://example.com/auth".to_string())?,
+///     Some(TokenUrl::new("https://example.com/token".to_string())?),
+/// );
+///
+/// let request = client.exchange_client_credentials();
+/// let http_request = request.prepare::<std::io::Error>()?;
+///
+/// // Send `http_request` using any HTTP client, and convert the response into an
+/// // `HttpResponse`.
+/// let mut headers = HeaderMap::new();
+/// headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+/// let http_response = HttpResponse {
+///     status_code: StatusCode::OK,
+///     headers,
+///     body: br#"{"access_token": "12/34", "token_type": "bearer"}"#.to_vec(),
+/// };
+///
+/// let token_response = request.parse::<std::io::Error>(http_response)?;
+/// assert_eq!("12/34", token_response.access_token().secret());
+/// # Ok(())
+/// # }
+/// ```
+///
+pub trait EndpointRequest {
+    ///
+    /// Response returned by the endpoint upon success.
+    ///
+    type Output;
+    ///
+    /// Error response returned by the endpoint upon failure.
+    ///
+    type ErrorResponse: ErrorResponse + 'static;
+
+    ///
+    /// Returns the HTTP request to send to the authorization server.
+    ///
+    /// Fails if the request is invalid (e.g., a required endpoint URL is not configured) or the
+    /// client secret cannot be resolved.
+    ///
+    fn prepare<RE>(&self) -> Result<HttpRequest, RequestTokenError<RE, Self::ErrorResponse>>
+    where
+        RE: Error + 'static;
+
+    ///
+    /// Parses the HTTP response returned by the authorization server.
+    ///
+    fn parse<RE>(
+        &self,
+        http_response: HttpResponse,
+    ) -> Result<Self::Output, RequestTokenError<RE, Self::ErrorResponse>>
+    where
+        RE: Error + 'static;
+}
+
+///
+/// An [`EndpointRequest`] authenticated with a client secret, which is resolved each time the
+/// request is sent.
+///
+trait ClientSecretRequest: EndpointRequest {
+    fn client_secret_source(&self) -> ClientSecretSource<'_>;
+
+    fn prepare_request<RE>(
+        &self,
+        client_secret: Option<&ClientSecret>,
+    ) -> Result<HttpRequest, RequestTokenError<RE, Self::ErrorResponse>>
+    where
+        RE: Error + 'static;
+}
+
 ///
 /// A request to exchange an authorization code for an access token.
 ///
@@ -1152,10 +1838,12 @@ where
 {
     auth_type: &'a AuthType,
     client_id: &'a ClientId,
-    client_secret: Option<&'a ClientSecret>,
+    client_secret: ClientSecretSource<'a>,
     code: AuthorizationCode,
+    expected_pkce_challenge: Option<PkceCodeChallenge>,
     extra_params: Vec<(Cow<'a, str>, Cow<'a, str>)>,
     pkce_verifier: Option<PkceCodeVerifier>,
+    strict: bool,
     token_url: Option<&'a TokenUrl>,
     redirect_url: Option<Cow<'a, RedirectUrl>>,
     _phantom: PhantomData<(TE, TR, TT)>,
@@ -1202,6 +1890,50 @@ where
         self
     }
 
+    ///
+    /// Sets the PKCE code challenge that was sent in the authorization request, which the code
+    /// verifier (see [`set_pkce_verifier`](Self::set_pkce_verifier)) must match before the
+    /// request is sent to the authorization server.
+    ///
+    /// This method must be called in strict mode (see [`Client::set_strict_mode`]), which
+    /// requires a verifier matching an `S256` challenge.
+    ///
+    pub fn set_expected_pkce_challenge(mut self, pkce_challenge: PkceCodeChallenge) -> Self {
+        self.expected_pkce_challenge = Some(pkce_challenge);
+        self
+    }
+
+    fn check_pkce_verifier(&self) -> Result<(), ConfigurationError> {
+        let pkce_verifier = match self.pkce_verifier {
+            Some(ref pkce_verifier) => pkce_verifier,
+            None if self.strict || self.expected_pkce_challenge.is_some() => {
+                return Err(ConfigurationError::PkceVerifierRequired)
+            }
+            None => return Ok(()),
+        };
+        if self.strict {
+            match self.expected_pkce_challenge {
+                Some(ref expected) if expected.method().as_str() == "S256" => {}
+                _ => return Err(ConfigurationError::PkceChallengeRequired),
+            }
+        }
+        if let Some(ref expected) = self.expected_pkce_challenge {
+            let matches = if expected.method().as_str() == "S256" {
+                // Verifiers outside the length bounds of RFC 7636 cannot have produced a valid
+                // challenge (and would cause `from_code_verifier_sha256` to panic).
+                (43..=128).contains(&pkce_verifier.secret().len())
+                    && PkceCodeChallenge::from_code_verifier_sha256(pkce_verifier).as_str()
+                        == expected.as_str()
+            } else {
+                pkce_verifier.secret() == expected.as_str()
+            };
+            if !matches {
+                return Err(ConfigurationError::PkceVerifierMismatch);
+            }
+        }
+        Ok(())
+    }
+
     ///
     /// Overrides the `redirect_url` to the one specified.
     ///
@@ -1210,10 +1942,52 @@ where
         self
     }
 
-    fn prepare_request<RE>(self) -> Result<HttpRequest, RequestTokenError<RE, TE>>
+    ///
+    /// Synchronously sends the request to the authorization server and awaits a response.
+    ///
+    pub fn request<F, RE>(self, http_client: F) -> Result<TR, RequestTokenError<RE, TE>>
+    where
+        F: FnOnce(HttpRequest) -> Result<HttpResponse, RE>,
+        RE: Error + 'static,
+    {
+        request_endpoint(&self, http_client)
+    }
+
+    ///
+    /// Asynchronously sends the request to the authorization server and returns a Future.
+    ///
+    pub async fn request_async<C, F, RE>(
+        self,
+        http_client: C,
+    ) -> Result<TR, RequestTokenError<RE, TE>>
     where
+        C: FnOnce(HttpRequest) -> F,
+        F: Future<Output = Result<HttpResponse, RE>>,
         RE: Error + 'static,
     {
+        request_endpoint_async(&self, http_client).await
+    }
+}
+
+impl<'a, TE, TR, TT> ClientSecretRequest for CodeTokenRequest<'a, TE, TR, TT>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+{
+    fn client_secret_source(&self) -> ClientSecretSource<'_> {
+        self.client_secret
+    }
+
+    fn prepare_request<RE>(
+        &self,
+        client_secret: Option<&ClientSecret>,
+    ) -> Result<HttpRequest, RequestTokenError<RE, TE>>
+    where
+        RE: Error + 'static,
+    {
+        self.check_pkce_verifier().map_err(strict_mode_error)?;
+
         let mut params = vec![
             ("grant_type", "authorization_code"),
             ("code", self.code.secret()),
@@ -1225,46 +1999,36 @@ where
         Ok(endpoint_request(
             self.auth_type,
             self.client_id,
-            self.client_secret,
+            client_secret,
             &self.extra_params,
-            self.redirect_url,
+            self.redirect_url.clone(),
             None,
-            self.token_url
-                .ok_or_else(|| RequestTokenError::Other("no token_url provided".to_string()))?
-                .url(),
+            token_endpoint_url(self.token_url, self.strict)?,
             params,
         ))
     }
+}
 
-    ///
-    /// Synchronously sends the request to the authorization server and awaits a response.
-    ///
-    pub fn request<F, RE>(self, http_client: F) -> Result<TR, RequestTokenError<RE, TE>>
+impl<'a, TE, TR, TT> EndpointRequest for CodeTokenRequest<'a, TE, TR, TT>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+{
+    type Output = TR;
+    type ErrorResponse = TE;
+
+    fn prepare<RE>(&self) -> Result<HttpRequest, RequestTokenError<RE, TE>>
     where
-        F: FnOnce(HttpRequest) -> Result<HttpResponse, RE>,
         RE: Error + 'static,
     {
-        http_client(self.prepare_request()?)
-            .map_err(RequestTokenError::Request)
-            .and_then(endpoint_response)
+        self.prepare_request(self.client_secret.resolve()?.secret())
     }
 
-    ///
-    /// Asynchronously sends the request to the authorization server and returns a Future.
-    ///
-    pub async fn request_async<C, F, RE>(
-        self,
-        http_client: C,
-    ) -> Result<TR, RequestTokenError<RE, TE>>
+    fn parse<RE>(&self, http_response: HttpResponse) -> Result<TR, RequestTokenError<RE, TE>>
     where
-        C: FnOnce(HttpRequest) -> F,
-        F: Future<Output = Result<HttpResponse, RE>>,
         RE: Error + 'static,
     {
-        let http_request = self.prepare_request()?;
-        let http_response = http_client(http_request)
-            .await
-            .map_err(RequestTokenError::Request)?;
         endpoint_response(http_response)
     }
 }
@@ -1283,10 +2047,11 @@ where
 {
     auth_type: &'a AuthType,
     client_id: &'a ClientId,
-    client_secret: Option<&'a ClientSecret>,
+    client_secret: ClientSecretSource<'a>,
     extra_params: Vec<(Cow<'a, str>, Cow<'a, str>)>,
     refresh_token: &'a RefreshToken,
     scopes: Vec<Cow<'a, Scope>>,
+    strict: bool,
     token_url: Option<&'a TokenUrl>,
     _phantom: PhantomData<(TE, TR, TT)>,
 }
@@ -1347,9 +2112,7 @@ where
         F: FnOnce(HttpRequest) -> Result<HttpResponse, RE>,
         RE: Error + 'static,
     {
-        http_client(self.prepare_request()?)
-            .map_err(RequestTokenError::Request)
-            .and_then(endpoint_response)
+        request_endpoint(&self, http_client)
     }
     ///
     /// Asynchronously sends the request to the authorization server and awaits a response.
@@ -1363,27 +2126,35 @@ where
         F: Future<Output = Result<HttpResponse, RE>>,
         RE: Error + 'static,
     {
-        let http_request = self.prepare_request()?;
-        let http_response = http_client(http_request)
-            .await
-            .map_err(RequestTokenError::Request)?;
-        endpoint_response(http_response)
+        request_endpoint_async(&self, http_client).await
     }
+}
 
-    fn prepare_request<RE>(&self) -> Result<HttpRequest, RequestTokenError<RE, TE>>
+impl<'a, TE, TR, TT> ClientSecretRequest for RefreshTokenRequest<'a, TE, TR, TT>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+{
+    fn client_secret_source(&self) -> ClientSecretSource<'_> {
+        self.client_secret
+    }
+
+    fn prepare_request<RE>(
+        &self,
+        client_secret: Option<&ClientSecret>,
+    ) -> Result<HttpRequest, RequestTokenError<RE, TE>>
     where
         RE: Error + 'static,
     {
         Ok(endpoint_request(
             self.auth_type,
             self.client_id,
-            self.client_secret,
+            client_secret,
             &self.extra_params,
             None,
             Some(&self.scopes),
-            self.token_url
-                .ok_or_else(|| RequestTokenError::Other("no token_url provided".to_string()))?
-                .url(),
+            token_endpoint_url(self.token_url, self.strict)?,
             vec![
                 ("grant_type", "refresh_token"),
                 ("refresh_token", self.refresh_token.secret()),
@@ -1392,6 +2163,30 @@ where
     }
 }
 
+impl<'a, TE, TR, TT> EndpointRequest for RefreshTokenRequest<'a, TE, TR, TT>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+{
+    type Output = TR;
+    type ErrorResponse = TE;
+
+    fn prepare<RE>(&self) -> Result<HttpRequest, RequestTokenError<RE, TE>>
+    where
+        RE: Error + 'static,
+    {
+        self.prepare_request(self.client_secret.resolve()?.secret())
+    }
+
+    fn parse<RE>(&self, http_response: HttpResponse) -> Result<TR, RequestTokenError<RE, TE>>
+    where
+        RE: Error + 'static,
+    {
+        endpoint_response(http_response)
+    }
+}
+
 ///
 /// A request to exchange resource owner credentials for an access token.
 ///
@@ -1406,7 +2201,7 @@ where
 {
     auth_type: &'a AuthType,
     client_id: &'a ClientId,
-    client_secret: Option<&'a ClientSecret>,
+    client_secret: ClientSecretSource<'a>,
     extra_params: Vec<(Cow<'a, str>, Cow<'a, str>)>,
     username: &'a ResourceOwnerUsername,
     password: &'a ResourceOwnerPassword,
@@ -1471,9 +2266,7 @@ where
         F: FnOnce(HttpRequest) -> Result<HttpResponse, RE>,
         RE: Error + 'static,
     {
-        http_client(self.prepare_request()?)
-            .map_err(RequestTokenError::Request)
-            .and_then(endpoint_response)
+        request_endpoint(&self, http_client)
     }
 
     ///
@@ -1488,27 +2281,35 @@ where
         F: Future<Output = Result<HttpResponse, RE>>,
         RE: Error + 'static,
     {
-        let http_request = self.prepare_request()?;
-        let http_response = http_client(http_request)
-            .await
-            .map_err(RequestTokenError::Request)?;
-        endpoint_response(http_response)
+        request_endpoint_async(&self, http_client).await
     }
+}
 
-    fn prepare_request<RE>(&self) -> Result<HttpRequest, RequestTokenError<RE, TE>>
+impl<'a, TE, TR, TT> ClientSecretRequest for PasswordTokenRequest<'a, TE, TR, TT>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+{
+    fn client_secret_source(&self) -> ClientSecretSource<'_> {
+        self.client_secret
+    }
+
+    fn prepare_request<RE>(
+        &self,
+        client_secret: Option<&ClientSecret>,
+    ) -> Result<HttpRequest, RequestTokenError<RE, TE>>
     where
         RE: Error + 'static,
     {
         Ok(endpoint_request(
             self.auth_type,
             self.client_id,
-            self.client_secret,
+            client_secret,
             &self.extra_params,
             None,
             Some(&self.scopes),
-            self.token_url
-                .ok_or_else(|| RequestTokenError::Other("no token_url provided".to_string()))?
-                .url(),
+            token_endpoint_url(self.token_url, false)?,
             vec![
                 ("grant_type", "password"),
                 ("username", self.username),
@@ -1518,6 +2319,30 @@ where
     }
 }
 
+impl<'a, TE, TR, TT> EndpointRequest for PasswordTokenRequest<'a, TE, TR, TT>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+{
+    type Output = TR;
+    type ErrorResponse = TE;
+
+    fn prepare<RE>(&self) -> Result<HttpRequest, RequestTokenError<RE, TE>>
+    where
+        RE: Error + 'static,
+    {
+        self.prepare_request(self.client_secret.resolve()?.secret())
+    }
+
+    fn parse<RE>(&self, http_response: HttpResponse) -> Result<TR, RequestTokenError<RE, TE>>
+    where
+        RE: Error + 'static,
+    {
+        endpoint_response(http_response)
+    }
+}
+
 ///
 /// A request to exchange client credentials for an access token.
 ///
@@ -1532,9 +2357,10 @@ where
 {
     auth_type: &'a AuthType,
     client_id: &'a ClientId,
-    client_secret: Option<&'a ClientSecret>,
+    client_secret: ClientSecretSource<'a>,
     extra_params: Vec<(Cow<'a, str>, Cow<'a, str>)>,
     scopes: Vec<Cow<'a, Scope>>,
+    strict: bool,
     token_url: Option<&'a TokenUrl>,
     _phantom: PhantomData<(TE, TR, TT)>,
 }
@@ -1595,9 +2421,7 @@ where
         F: FnOnce(HttpRequest) -> Result<HttpResponse, RE>,
         RE: Error + 'static,
     {
-        http_client(self.prepare_request()?)
-            .map_err(RequestTokenError::Request)
-            .and_then(endpoint_response)
+        request_endpoint(&self, http_client)
     }
 
     ///
@@ -1612,34 +2436,66 @@ where
         F: Future<Output = Result<HttpResponse, RE>>,
         RE: Error + 'static,
     {
-        let http_request = self.prepare_request()?;
-        let http_response = http_client(http_request)
-            .await
-            .map_err(RequestTokenError::Request)?;
-        endpoint_response(http_response)
+        request_endpoint_async(&self, http_client).await
+    }
+}
+
+impl<'a, TE, TR, TT> ClientSecretRequest for ClientCredentialsTokenRequest<'a, TE, TR, TT>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+{
+    fn client_secret_source(&self) -> ClientSecretSource<'_> {
+        self.client_secret
     }
 
-    fn prepare_request<RE>(&self) -> Result<HttpRequest, RequestTokenError<RE, TE>>
+    fn prepare_request<RE>(
+        &self,
+        client_secret: Option<&ClientSecret>,
+    ) -> Result<HttpRequest, RequestTokenError<RE, TE>>
     where
         RE: Error + 'static,
     {
         Ok(endpoint_request(
             self.auth_type,
             self.client_id,
-            self.client_secret,
+            client_secret,
             &self.extra_params,
             None,
             Some(&self.scopes),
-            self.token_url
-                .ok_or_else(|| RequestTokenError::Other("no token_url provided".to_string()))?
-                .url(),
+            token_endpoint_url(self.token_url, self.strict)?,
             vec![("grant_type", "client_credentials")],
         ))
     }
 }
 
+impl<'a, TE, TR, TT> EndpointRequest for ClientCredentialsTokenRequest<'a, TE, TR, TT>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+{
+    type Output = TR;
+    type ErrorResponse = TE;
+
+    fn prepare<RE>(&self) -> Result<HttpRequest, RequestTokenError<RE, TE>>
+    where
+        RE: Error + 'static,
+    {
+        self.prepare_request(self.client_secret.resolve()?.secret())
+    }
+
+    fn parse<RE>(&self, http_response: HttpResponse) -> Result<TR, RequestTokenError<RE, TE>>
+    where
+        RE: Error + 'static,
+    {
+        endpoint_response(http_response)
+    }
+}
+
 ///
-/// A request to introspect an access token.
+/// A request to introspect a token.
 ///
 /// See https://tools.ietf.org/html/rfc7662#section-2.1
 ///
@@ -1650,12 +2506,12 @@ where
     TIR: TokenIntrospectionResponse<TT>,
     TT: TokenType,
 {
-    token: &'a AccessToken,
+    token: &'a dyn IntrospectableToken,
     token_type_hint: Option<Cow<'a, str>>,
 
     auth_type: &'a AuthType,
     client_id: &'a ClientId,
-    client_secret: Option<&'a ClientSecret>,
+    client_secret: ClientSecretSource<'a>,
     extra_params: Vec<(Cow<'a, str>, Cow<'a, str>)>,
     introspection_url: &'a IntrospectionUrl,
 
@@ -1716,19 +2572,136 @@ where
         self
     }
 
-    fn prepare_request<RE>(self) -> Result<HttpRequest, RequestTokenError<RE, TE>>
+    ///
+    /// Synchronously sends the request to the authorization server and awaits a response.
+    ///
+    pub fn request<F, RE>(self, http_client: F) -> Result<TIR, RequestTokenError<RE, TE>>
+    where
+        F: FnOnce(HttpRequest) -> Result<HttpResponse, RE>,
+        RE: Error + 'static,
+    {
+        request_endpoint(&self, http_client)
+    }
+
+    ///
+    /// Asynchronously sends the request to the authorization server and returns a Future.
+    ///
+    pub async fn request_async<C, F, RE>(
+        self,
+        http_client: C,
+    ) -> Result<TIR, RequestTokenError<RE, TE>>
+    where
+        C: FnOnce(HttpRequest) -> F,
+        F: Future<Output = Result<HttpResponse, RE>>,
+        RE: Error + 'static,
+    {
+        request_endpoint_async(&self, http_client).await
+    }
+
+    ///
+    /// Synchronously requests a JWT response
+    /// ([RFC 9701](https://tools.ietf.org/html/rfc9701)) from the authorization server and
+    /// verifies it with `verifier`, fetching the server's keys with `http_client` if needed.
+    /// Requires "jwt" feature.
+    ///
+    #[cfg(feature = "jwt")]
+    pub fn request_jwt<F, RE>(
+        self,
+        verifier: &jwt::JwtIntrospectionVerifier,
+        http_client: F,
+    ) -> Result<jwt::JwtIntrospectionResponse<TIR>, RequestTokenError<RE, TE>>
+    where
+        F: Fn(HttpRequest) -> Result<HttpResponse, RE>,
+        RE: Error + 'static,
+    {
+        let http_response = send_request(
+            self.client_secret,
+            |client_secret| self.prepare_jwt_request(client_secret),
+            &http_client,
+        )?;
+        check_response_status(&http_response)?;
+        verifier
+            .verify(http_response, http_client)
+            .map_err(jwt::JwtValidationError::into_request_error)
+    }
+
+    ///
+    /// Asynchronously requests a JWT response
+    /// ([RFC 9701](https://tools.ietf.org/html/rfc9701)) from the authorization server and
+    /// verifies it with `verifier`, fetching the server's keys with `http_client` if needed.
+    /// Requires "jwt" feature.
+    ///
+    #[cfg(feature = "jwt")]
+    pub async fn request_jwt_async<C, F, RE>(
+        self,
+        verifier: &jwt::JwtIntrospectionVerifier,
+        http_client: C,
+    ) -> Result<jwt::JwtIntrospectionResponse<TIR>, RequestTokenError<RE, TE>>
+    where
+        C: Fn(HttpRequest) -> F,
+        F: Future<Output = Result<HttpResponse, RE>>,
+        RE: Error + 'static,
+    {
+        let http_response = send_request_async(
+            self.client_secret,
+            |client_secret| self.prepare_jwt_request(client_secret),
+            &http_client,
+        )
+        .await?;
+        check_response_status(&http_response)?;
+        verifier
+            .verify_async(http_response, http_client)
+            .await
+            .map_err(jwt::JwtValidationError::into_request_error)
+    }
+
+    #[cfg(feature = "jwt")]
+    fn prepare_jwt_request<RE>(
+        &self,
+        client_secret: Option<&ClientSecret>,
+    ) -> Result<HttpRequest, RequestTokenError<RE, TE>>
+    where
+        RE: Error + 'static,
+    {
+        let mut http_request = self.prepare_request(client_secret)?;
+        http_request.headers.insert(
+            ACCEPT,
+            HeaderValue::from_static(jwt::CONTENT_TYPE_INTROSPECTION_JWT),
+        );
+        Ok(http_request)
+    }
+}
+
+impl<'a, TE, TIR, TT> ClientSecretRequest for IntrospectionRequest<'a, TE, TIR, TT>
+where
+    TE: ErrorResponse + 'static,
+    TIR: TokenIntrospectionResponse<TT>,
+    TT: TokenType,
+{
+    fn client_secret_source(&self) -> ClientSecretSource<'_> {
+        self.client_secret
+    }
+
+    fn prepare_request<RE>(
+        &self,
+        client_secret: Option<&ClientSecret>,
+    ) -> Result<HttpRequest, RequestTokenError<RE, TE>>
     where
         RE: Error + 'static,
     {
         let mut params: Vec<(&str, &str)> = vec![("token", self.token.secret())];
-        if let Some(ref token_type_hint) = self.token_type_hint {
+        let token_type_hint = match self.token_type_hint {
+            Some(ref token_type_hint) => Some(token_type_hint.as_ref()),
+            None => self.token.type_hint(),
+        };
+        if let Some(token_type_hint) = token_type_hint {
             params.push(("token_type_hint", token_type_hint));
         }
 
         Ok(endpoint_request(
             self.auth_type,
             self.client_id,
-            self.client_secret,
+            client_secret,
             &self.extra_params,
             None,
             None,
@@ -1736,36 +2709,28 @@ where
             params,
         ))
     }
+}
 
-    ///
-    /// Synchronously sends the request to the authorization server and awaits a response.
-    ///
-    pub fn request<F, RE>(self, http_client: F) -> Result<TIR, RequestTokenError<RE, TE>>
+impl<'a, TE, TIR, TT> EndpointRequest for IntrospectionRequest<'a, TE, TIR, TT>
+where
+    TE: ErrorResponse + 'static,
+    TIR: TokenIntrospectionResponse<TT>,
+    TT: TokenType,
+{
+    type Output = TIR;
+    type ErrorResponse = TE;
+
+    fn prepare<RE>(&self) -> Result<HttpRequest, RequestTokenError<RE, TE>>
     where
-        F: FnOnce(HttpRequest) -> Result<HttpResponse, RE>,
         RE: Error + 'static,
     {
-        http_client(self.prepare_request()?)
-            .map_err(RequestTokenError::Request)
-            .and_then(endpoint_response)
+        self.prepare_request(self.client_secret.resolve()?.secret())
     }
 
-    ///
-    /// Asynchronously sends the request to the authorization server and returns a Future.
-    ///
-    pub async fn request_async<C, F, RE>(
-        self,
-        http_client: C,
-    ) -> Result<TIR, RequestTokenError<RE, TE>>
+    fn parse<RE>(&self, http_response: HttpResponse) -> Result<TIR, RequestTokenError<RE, TE>>
     where
-        C: FnOnce(HttpRequest) -> F,
-        F: Future<Output = Result<HttpResponse, RE>>,
         RE: Error + 'static,
     {
-        let http_request = self.prepare_request()?;
-        let http_response = http_client(http_request)
-            .await
-            .map_err(RequestTokenError::Request)?;
         endpoint_response(http_response)
     }
 }
@@ -1784,7 +2749,7 @@ where
 
     auth_type: &'a AuthType,
     client_id: &'a ClientId,
-    client_secret: Option<&'a ClientSecret>,
+    client_secret: ClientSecretSource<'a>,
     extra_params: Vec<(Cow<'a, str>, Cow<'a, str>)>,
     revocation_url: &'a RevocationUrl,
 
@@ -1820,7 +2785,55 @@ where
         self
     }
 
-    fn prepare_request<RE>(self) -> Result<HttpRequest, RequestTokenError<RE, TE>>
+    ///
+    /// Synchronously sends the request to the authorization server and awaits a response.
+    ///
+    /// A successful response indicates that the server either revoked the token or the token was not known to the
+    /// server.
+    ///
+    /// Error [`UnsupportedTokenType`](crate::revocation::RevocationErrorResponseType::UnsupportedTokenType) will be returned if the
+    /// type of token type given is not supported by the server.
+    ///
+    pub fn request<F, RE>(self, http_client: F) -> Result<(), RequestTokenError<RE, TE>>
+    where
+        F: FnOnce(HttpRequest) -> Result<HttpResponse, RE>,
+        RE: Error + 'static,
+    {
+        // From https://tools.ietf.org/html/rfc7009#section-2.2:
+        //   "The content of the response body is ignored by the client as all
+        //    necessary information is conveyed in the response code."
+        request_endpoint(&self, http_client)
+    }
+
+    ///
+    /// Asynchronously sends the request to the authorization server and returns a Future.
+    ///
+    pub async fn request_async<C, F, RE>(
+        self,
+        http_client: C,
+    ) -> Result<(), RequestTokenError<RE, TE>>
+    where
+        C: FnOnce(HttpRequest) -> F,
+        F: Future<Output = Result<HttpResponse, RE>>,
+        RE: Error + 'static,
+    {
+        request_endpoint_async(&self, http_client).await
+    }
+}
+
+impl<'a, RT, TE> ClientSecretRequest for RevocationRequest<'a, RT, TE>
+where
+    RT: RevocableToken,
+    TE: ErrorResponse + 'static,
+{
+    fn client_secret_source(&self) -> ClientSecretSource<'_> {
+        self.client_secret
+    }
+
+    fn prepare_request<RE>(
+        &self,
+        client_secret: Option<&ClientSecret>,
+    ) -> Result<HttpRequest, RequestTokenError<RE, TE>>
     where
         RE: Error + 'static,
     {
@@ -1832,7 +2845,7 @@ where
         Ok(endpoint_request(
             self.auth_type,
             self.client_id,
-            self.client_secret,
+            client_secret,
             &self.extra_params,
             None,
             None,
@@ -1840,49 +2853,229 @@ where
             params,
         ))
     }
+}
+
+impl<'a, RT, TE> EndpointRequest for RevocationRequest<'a, RT, TE>
+where
+    RT: RevocableToken,
+    TE: ErrorResponse + 'static,
+{
+    type Output = ();
+    type ErrorResponse = TE;
 
+    fn prepare<RE>(&self) -> Result<HttpRequest, RequestTokenError<RE, TE>>
+    where
+        RE: Error + 'static,
+    {
+        self.prepare_request(self.client_secret.resolve()?.secret())
+    }
+
+    fn parse<RE>(&self, http_response: HttpResponse) -> Result<(), RequestTokenError<RE, TE>>
+    where
+        RE: Error + 'static,
+    {
+        endpoint_response_status_only(http_response)
+    }
+}
+
+///
+/// A request to revoke the tokens in a token response via an
+/// [`RFC 7009`](https://tools.ietf.org/html/rfc7009#section-2.1) compatible endpoint.
+///
+/// See [`Client::revoke_token_response`].
+///
+#[derive(Debug)]
+pub struct TokenResponseRevocationRequest<'a, RT, TE>
+where
+    RT: RevocableToken,
+    TE: ErrorResponse,
+{
+    refresh_token: Option<RevocationRequest<'a, RT, TE>>,
+    access_token: RevocationRequest<'a, RT, TE>,
+}
+
+impl<'a, RT, TE> TokenResponseRevocationRequest<'a, RT, TE>
+where
+    RT: RevocableToken,
+    TE: RevocationErrorResponse + 'static,
+{
     ///
-    /// Synchronously sends the request to the authorization server and awaits a response.
+    /// Appends an extra param to each of the token revocation requests.
     ///
-    /// A successful response indicates that the server either revoked the token or the token was not known to the
-    /// server.
+    /// See [`RevocationRequest::add_extra_param`].
     ///
-    /// Error [`UnsupportedTokenType`](crate::revocation::RevocationErrorResponseType::UnsupportedTokenType) will be returned if the
-    /// type of token type given is not supported by the server.
+    pub fn add_extra_param<N, V>(mut self, name: N, value: V) -> Self
+    where
+        N: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        let (name, value) = (name.into(), value.into());
+        self.refresh_token = self
+            .refresh_token
+            .map(|request| request.add_extra_param(name.clone(), value.clone()));
+        self.access_token = self.access_token.add_extra_param(name, value);
+        self
+    }
+
+    ///
+    /// Synchronously sends the requests to the authorization server and returns the outcome for
+    /// each token.
     ///
-    pub fn request<F, RE>(self, http_client: F) -> Result<(), RequestTokenError<RE, TE>>
+    pub fn request<F, RE>(self, http_client: F) -> revocation::TokenResponseRevocation<RE, TE>
     where
-        F: FnOnce(HttpRequest) -> Result<HttpResponse, RE>,
+        F: Fn(HttpRequest) -> Result<HttpResponse, RE>,
         RE: Error + 'static,
     {
-        // From https://tools.ietf.org/html/rfc7009#section-2.2:
-        //   "The content of the response body is ignored by the client as all
-        //    necessary information is conveyed in the response code."
-        http_client(self.prepare_request()?)
-            .map_err(RequestTokenError::Request)
-            .and_then(endpoint_response_status_only)
+        let refresh_token = match self.refresh_token {
+            Some(request) => revocation_outcome(request.request(&http_client)),
+            None => revocation::RevocationOutcome::NotAttempted,
+        };
+        let access_token = if should_revoke_access_token(&refresh_token) {
+            revocation_outcome(self.access_token.request(&http_client))
+        } else {
+            revocation::RevocationOutcome::NotAttempted
+        };
+        revocation::TokenResponseRevocation {
+            refresh_token,
+            access_token,
+        }
     }
 
     ///
-    /// Asynchronously sends the request to the authorization server and returns a Future.
+    /// Asynchronously sends the requests to the authorization server and returns a Future
+    /// resolving to the outcome for each token.
     ///
     pub async fn request_async<C, F, RE>(
         self,
         http_client: C,
-    ) -> Result<(), RequestTokenError<RE, TE>>
+    ) -> revocation::TokenResponseRevocation<RE, TE>
     where
-        C: FnOnce(HttpRequest) -> F,
+        C: Fn(HttpRequest) -> F,
         F: Future<Output = Result<HttpResponse, RE>>,
         RE: Error + 'static,
     {
-        let http_request = self.prepare_request()?;
-        let http_response = http_client(http_request)
-            .await
-            .map_err(RequestTokenError::Request)?;
-        endpoint_response_status_only(http_response)
+        let refresh_token = match self.refresh_token {
+            Some(request) => revocation_outcome(request.request_async(&http_client).await),
+            None => revocation::RevocationOutcome::NotAttempted,
+        };
+        let access_token = if should_revoke_access_token(&refresh_token) {
+            revocation_outcome(self.access_token.request_async(&http_client).await)
+        } else {
+            revocation::RevocationOutcome::NotAttempted
+        };
+        revocation::TokenResponseRevocation {
+            refresh_token,
+            access_token,
+        }
+    }
+}
+
+fn revocation_outcome<RE, TE>(
+    result: Result<(), RequestTokenError<RE, TE>>,
+) -> revocation::RevocationOutcome<RE, TE>
+where
+    RE: Error + 'static,
+    TE: ErrorResponse + 'static,
+{
+    match result {
+        Ok(()) => revocation::RevocationOutcome::Revoked,
+        Err(err) => revocation::RevocationOutcome::Failed(err),
+    }
+}
+
+fn should_revoke_access_token<RE, TE>(
+    refresh_token_outcome: &revocation::RevocationOutcome<RE, TE>,
+) -> bool
+where
+    RE: Error + 'static,
+    TE: RevocationErrorResponse + 'static,
+{
+    match refresh_token_outcome {
+        revocation::RevocationOutcome::NotAttempted => true,
+        revocation::RevocationOutcome::Failed(RequestTokenError::ServerResponse(error)) => {
+            error.is_unsupported_token_type()
+        }
+        revocation::RevocationOutcome::Revoked | revocation::RevocationOutcome::Failed(_) => false,
     }
 }
 
+fn request_endpoint<R, F, RE>(
+    request: &R,
+    http_client: F,
+) -> Result<R::Output, RequestTokenError<RE, R::ErrorResponse>>
+where
+    R: ClientSecretRequest,
+    F: FnOnce(HttpRequest) -> Result<HttpResponse, RE>,
+    RE: Error + 'static,
+{
+    let http_response = send_request(
+        request.client_secret_source(),
+        |client_secret| request.prepare_request(client_secret),
+        http_client,
+    )?;
+    request.parse(http_response)
+}
+
+async fn request_endpoint_async<R, C, F, RE>(
+    request: &R,
+    http_client: C,
+) -> Result<R::Output, RequestTokenError<RE, R::ErrorResponse>>
+where
+    R: ClientSecretRequest,
+    C: FnOnce(HttpRequest) -> F,
+    F: Future<Output = Result<HttpResponse, RE>>,
+    RE: Error + 'static,
+{
+    let http_response = send_request_async(
+        request.client_secret_source(),
+        |client_secret| request.prepare_request(client_secret),
+        http_client,
+    )
+    .await?;
+    request.parse(http_response)
+}
+
+// Sends the request prepared by `prepare`. If the client secret is rejected, the credential
+// provider is notified so that re-issuing the request may use a different secret.
+fn send_request<P, F, RE, TE>(
+    client_secret: ClientSecretSource<'_>,
+    prepare: P,
+    http_client: F,
+) -> Result<HttpResponse, RequestTokenError<RE, TE>>
+where
+    P: FnOnce(Option<&ClientSecret>) -> Result<HttpRequest, RequestTokenError<RE, TE>>,
+    F: FnOnce(HttpRequest) -> Result<HttpResponse, RE>,
+    RE: Error + 'static,
+    TE: ErrorResponse + 'static,
+{
+    let client_secret = client_secret.resolve()?;
+    let http_response =
+        http_client(prepare(client_secret.secret())?).map_err(RequestTokenError::Request)?;
+    client_secret.check_response(&http_response);
+    Ok(http_response)
+}
+
+async fn send_request_async<P, C, F, RE, TE>(
+    client_secret: ClientSecretSource<'_>,
+    prepare: P,
+    http_client: C,
+) -> Result<HttpResponse, RequestTokenError<RE, TE>>
+where
+    P: FnOnce(Option<&ClientSecret>) -> Result<HttpRequest, RequestTokenError<RE, TE>>,
+    C: FnOnce(HttpRequest) -> F,
+    F: Future<Output = Result<HttpResponse, RE>>,
+    RE: Error + 'static,
+    TE: ErrorResponse + 'static,
+{
+    let client_secret = client_secret.resolve()?;
+    let http_request = prepare(client_secret.secret())?;
+    let http_response = http_client(http_request)
+        .await
+        .map_err(RequestTokenError::Request)?;
+    client_secret.check_response(&http_response);
+    Ok(http_response)
+}
+
 #[allow(clippy::too_many_arguments)]
 fn endpoint_request<'a>(
     auth_type: &'a AuthType,
@@ -2073,19 +3266,34 @@ where
 ///
 /// See https://tools.ietf.org/html/rfc8628#section-3.1.
 ///
-#[derive(Debug)]
 pub struct DeviceAuthorizationRequest<'a, TE>
 where
     TE: ErrorResponse,
 {
     auth_type: &'a AuthType,
     client_id: &'a ClientId,
-    client_secret: Option<&'a ClientSecret>,
+    client_secret: ClientSecretSource<'a>,
     extra_params: Vec<(Cow<'a, str>, Cow<'a, str>)>,
     scopes: Vec<Cow<'a, Scope>>,
     device_authorization_url: &'a DeviceAuthorizationUrl,
+    time_fn: Arc<dyn Fn() -> DateTime<Utc> + 'a + Send + Sync>,
     _phantom: PhantomData<TE>,
 }
+impl<'a, TE> Debug for DeviceAuthorizationRequest<'a, TE>
+where
+    TE: ErrorResponse,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeviceAuthorizationRequest")
+            .field("auth_type", &self.auth_type)
+            .field("client_id", &self.client_id)
+            .field("client_secret", &self.client_secret)
+            .field("extra_params", &self.extra_params)
+            .field("scopes", &self.scopes)
+            .field("device_authorization_url", &self.device_authorization_url)
+            .finish()
+    }
+}
 
 impl<'a, TE> DeviceAuthorizationRequest<'a, TE>
 where
@@ -2134,20 +3342,18 @@ where
         self
     }
 
-    fn prepare_request<RE>(self) -> Result<HttpRequest, RequestTokenError<RE, TE>>
+    ///
+    /// Specifies a function for returning the current time.
+    ///
+    /// This function determines the time at which the response is received (see
+    /// [`DeviceAuthorizationResponse::issued_at`]), from which the device code expires.
+    ///
+    pub fn set_time_fn<T>(mut self, time_fn: T) -> Self
     where
-        RE: Error + 'static,
+        T: Fn() -> DateTime<Utc> + 'a + Send + Sync,
     {
-        Ok(endpoint_request(
-            self.auth_type,
-            self.client_id,
-            self.client_secret,
-            &self.extra_params,
-            None,
-            Some(&self.scopes),
-            self.device_authorization_url.url(),
-            vec![],
-        ))
+        self.time_fn = Arc::new(time_fn);
+        self
     }
 
     ///
@@ -2162,9 +3368,12 @@ where
         RE: Error + 'static,
         EF: ExtraDeviceAuthorizationFields,
     {
-        http_client(self.prepare_request()?)
-            .map_err(RequestTokenError::Request)
-            .and_then(endpoint_response)
+        let http_response = send_request(
+            self.client_secret,
+            |client_secret| self.prepare_request(client_secret),
+            http_client,
+        )?;
+        self.parse_response(http_response)
     }
 
     ///
@@ -2180,11 +3389,80 @@ where
         RE: Error + 'static,
         EF: ExtraDeviceAuthorizationFields,
     {
-        let http_request = self.prepare_request()?;
-        let http_response = http_client(http_request)
-            .await
-            .map_err(RequestTokenError::Request)?;
-        endpoint_response(http_response)
+        let http_response = send_request_async(
+            self.client_secret,
+            |client_secret| self.prepare_request(client_secret),
+            http_client,
+        )
+        .await?;
+        self.parse_response(http_response)
+    }
+
+    fn parse_response<RE, EF>(
+        &self,
+        http_response: HttpResponse,
+    ) -> Result<DeviceAuthorizationResponse<EF>, RequestTokenError<RE, TE>>
+    where
+        RE: Error + 'static,
+        EF: ExtraDeviceAuthorizationFields,
+    {
+        let mut device_auth_response: DeviceAuthorizationResponse<EF> =
+            endpoint_response(http_response)?;
+        device_auth_response.set_issued_at((*self.time_fn)());
+        Ok(device_auth_response)
+    }
+}
+
+impl<'a, TE> ClientSecretRequest for DeviceAuthorizationRequest<'a, TE>
+where
+    TE: ErrorResponse + 'static,
+{
+    fn client_secret_source(&self) -> ClientSecretSource<'_> {
+        self.client_secret
+    }
+
+    fn prepare_request<RE>(
+        &self,
+        client_secret: Option<&ClientSecret>,
+    ) -> Result<HttpRequest, RequestTokenError<RE, TE>>
+    where
+        RE: Error + 'static,
+    {
+        Ok(endpoint_request(
+            self.auth_type,
+            self.client_id,
+            client_secret,
+            &self.extra_params,
+            None,
+            Some(&self.scopes),
+            self.device_authorization_url.url(),
+            vec![],
+        ))
+    }
+}
+
+impl<'a, TE> EndpointRequest for DeviceAuthorizationRequest<'a, TE>
+where
+    TE: ErrorResponse + 'static,
+{
+    type Output = StandardDeviceAuthorizationResponse;
+    type ErrorResponse = TE;
+
+    fn prepare<RE>(&self) -> Result<HttpRequest, RequestTokenError<RE, TE>>
+    where
+        RE: Error + 'static,
+    {
+        self.prepare_request(self.client_secret.resolve()?.secret())
+    }
+
+    fn parse<RE>(
+        &self,
+        http_response: HttpResponse,
+    ) -> Result<StandardDeviceAuthorizationResponse, RequestTokenError<RE, TE>>
+    where
+        RE: Error + 'static,
+    {
+        self.parse_response(http_response)
     }
 }
 
@@ -2202,8 +3480,9 @@ where
 {
     auth_type: &'a AuthType,
     client_id: &'a ClientId,
-    client_secret: Option<&'a ClientSecret>,
+    client_secret: ClientSecretSource<'a>,
     extra_params: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+    strict: bool,
     token_url: Option<&'a TokenUrl>,
     dev_auth_resp: &'a DeviceAuthorizationResponse<EF>,
     time_fn: Arc<dyn Fn() -> DateTime<Utc> + 'b + Send + Sync>,
@@ -2253,10 +3532,35 @@ where
         self
     }
 
+    ///
+    /// Returns the initial polling state for this request, for applications that poll the
+    /// authorization server themselves (see [`DeviceAccessTokenPollState`]).
+    ///
+    /// The flow expires after `timeout` from the current time (see
+    /// [`set_time_fn`](Self::set_time_fn)) if specified, or otherwise when the device code
+    /// expires (see [`DeviceAuthorizationResponse::expires_at`]).
+    ///
+    pub fn poll_state<RE>(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<DeviceAccessTokenPollState, RequestTokenError<RE, DeviceCodeErrorResponse>>
+    where
+        RE: Error + 'static,
+    {
+        Ok(DeviceAccessTokenPollState::new(
+            self.dev_auth_resp.interval(),
+            self.compute_timeout(timeout)?,
+        ))
+    }
+
     ///
     /// Synchronously polls the authorization server for a response, waiting
     /// using a user defined sleep function.
     ///
+    /// Polling stops after `timeout` if specified, or otherwise when the device code expires (see
+    /// [`poll_state`](Self::poll_state)). Use [`poll_for_token`](Self::poll_for_token) to
+    /// distinguish an expired device code or a denied request from other errors.
+    ///
     pub fn request<F, S, RE>(
         self,
         http_client: F,
@@ -2268,32 +3572,67 @@ where
         S: Fn(Duration),
         RE: Error + 'static,
     {
-        // Get the request timeout and starting interval
-        let timeout_dt = self.compute_timeout(timeout)?;
-        let mut interval = self.dev_auth_resp.interval();
+        self.poll_for_token(http_client, sleep_fn, timeout)
+            .map_err(RequestTokenError::from)
+    }
+
+    ///
+    /// Synchronously polls the authorization server for a response like
+    /// [`request`](Self::request), but returns a [`DeviceAccessTokenError`] that identifies an
+    /// expired device code or a denied request.
+    ///
+    pub fn poll_for_token<F, S, RE>(
+        self,
+        http_client: F,
+        sleep_fn: S,
+        timeout: Option<Duration>,
+    ) -> Result<TR, DeviceAccessTokenError<RE>>
+    where
+        F: Fn(HttpRequest) -> Result<HttpResponse, RE>,
+        S: Fn(Duration),
+        RE: Error + 'static,
+    {
+        let mut state = self.poll_state(timeout)?;
 
         // Loop while requesting a token.
+        let mut retried_client_secret = false;
         loop {
-            let now = (*self.time_fn)();
-            if now > timeout_dt {
-                break Err(RequestTokenError::Other("Device code expired".to_string()));
+            if state.is_expired((*self.time_fn)()) {
+                break Err(DeviceAccessTokenError::Expired);
             }
 
-            match self.process_response(http_client(self.prepare_request()?), interval) {
-                DeviceAccessTokenPollResult::ContinueWithNewPollInterval(new_interval) => {
-                    interval = new_interval
+            let client_secret = self.client_secret.resolve()?;
+            let res = http_client(self.prepare_request(client_secret.secret())?);
+
+            // Retry once without waiting if the credential provider switched to a different
+            // client secret after the current one was rejected.
+            if let Ok(ref http_response) = res {
+                if client_secret.check_response(http_response) && !retried_client_secret {
+                    retried_client_secret = true;
+                    continue;
                 }
-                DeviceAccessTokenPollResult::Done(res, _) => break res,
+            }
+
+            match state.process_response(res) {
+                DeviceAccessTokenPollStatus::Pending(_)
+                | DeviceAccessTokenPollStatus::SlowDown(_)
+                | DeviceAccessTokenPollStatus::TransientError(..) => {}
+                DeviceAccessTokenPollStatus::Done(token) => break Ok(token),
+                DeviceAccessTokenPollStatus::Failed(err) => break Err(err),
             }
 
             // Sleep here using the provided sleep function.
-            sleep_fn(interval);
+            sleep_fn(state.interval());
         }
     }
 
     ///
     /// Asynchronously sends the request to the authorization server and awaits a response.
     ///
+    /// Polling stops after `timeout` if specified, or otherwise when the device code expires (see
+    /// [`poll_state`](Self::poll_state)). Use [`poll_for_token_async`](Self::poll_for_token_async)
+    /// to distinguish an expired device code or a denied request from other errors.
+    ///
     pub async fn request_async<C, F, S, SF, RE>(
         self,
         http_client: C,
@@ -2307,31 +3646,66 @@ where
         SF: Future<Output = ()>,
         RE: Error + 'static,
     {
-        // Get the request timeout and starting interval
-        let timeout_dt = self.compute_timeout(timeout)?;
-        let mut interval = self.dev_auth_resp.interval();
+        self.poll_for_token_async(http_client, sleep_fn, timeout)
+            .await
+            .map_err(RequestTokenError::from)
+    }
+
+    ///
+    /// Asynchronously polls the authorization server for a response like
+    /// [`request_async`](Self::request_async), but returns a [`DeviceAccessTokenError`] that
+    /// identifies an expired device code or a denied request.
+    ///
+    pub async fn poll_for_token_async<C, F, S, SF, RE>(
+        self,
+        http_client: C,
+        sleep_fn: S,
+        timeout: Option<Duration>,
+    ) -> Result<TR, DeviceAccessTokenError<RE>>
+    where
+        C: Fn(HttpRequest) -> F,
+        F: Future<Output = Result<HttpResponse, RE>>,
+        S: Fn(Duration) -> SF,
+        SF: Future<Output = ()>,
+        RE: Error + 'static,
+    {
+        let mut state = self.poll_state(timeout)?;
 
         // Loop while requesting a token.
+        let mut retried_client_secret = false;
         loop {
-            let now = (*self.time_fn)();
-            if now > timeout_dt {
-                break Err(RequestTokenError::Other("Device code expired".to_string()));
+            if state.is_expired((*self.time_fn)()) {
+                break Err(DeviceAccessTokenError::Expired);
             }
 
-            match self.process_response(http_client(self.prepare_request()?).await, interval) {
-                DeviceAccessTokenPollResult::ContinueWithNewPollInterval(new_interval) => {
-                    interval = new_interval
+            let client_secret = self.client_secret.resolve()?;
+            let res = http_client(self.prepare_request(client_secret.secret())?).await;
+
+            // Retry once without waiting if the credential provider switched to a different
+            // client secret after the current one was rejected.
+            if let Ok(ref http_response) = res {
+                if client_secret.check_response(http_response) && !retried_client_secret {
+                    retried_client_secret = true;
+                    continue;
                 }
-                DeviceAccessTokenPollResult::Done(res, _) => break res,
+            }
+
+            match state.process_response(res) {
+                DeviceAccessTokenPollStatus::Pending(_)
+                | DeviceAccessTokenPollStatus::SlowDown(_)
+                | DeviceAccessTokenPollStatus::TransientError(..) => {}
+                DeviceAccessTokenPollStatus::Done(token) => break Ok(token),
+                DeviceAccessTokenPollStatus::Failed(err) => break Err(err),
             }
 
             // Sleep here using the provided sleep function.
-            sleep_fn(interval).await;
+            sleep_fn(state.interval()).await;
         }
     }
 
     fn prepare_request<RE>(
         &self,
+        client_secret: Option<&ClientSecret>,
     ) -> Result<HttpRequest, RequestTokenError<RE, DeviceCodeErrorResponse>>
     where
         RE: Error + 'static,
@@ -2339,13 +3713,11 @@ where
         Ok(endpoint_request(
             self.auth_type,
             self.client_id,
-            self.client_secret,
+            client_secret,
             &self.extra_params,
             None,
             None,
-            self.token_url
-                .ok_or_else(|| RequestTokenError::Other("no token_url provided".to_string()))?
-                .url(),
+            token_endpoint_url(self.token_url, self.strict)?,
             vec![
                 ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
                 ("device_code", self.dev_auth_resp.device_code().secret()),
@@ -2353,74 +3725,201 @@ where
         ))
     }
 
-    fn process_response<RE>(
+    fn compute_timeout<RE>(
         &self,
-        res: Result<HttpResponse, RE>,
-        current_interval: Duration,
-    ) -> DeviceAccessTokenPollResult<TR, RE, DeviceCodeErrorResponse, TT>
+        timeout: Option<Duration>,
+    ) -> Result<DateTime<Utc>, RequestTokenError<RE, DeviceCodeErrorResponse>>
     where
         RE: Error + 'static,
     {
-        let http_response = match res {
-            Ok(inner) => inner,
-            Err(_) => {
-                // Try and double the current interval. If that fails, just use the current one.
-                let new_interval = current_interval.checked_mul(2).unwrap_or(current_interval);
-                return DeviceAccessTokenPollResult::ContinueWithNewPollInterval(new_interval);
+        let now = (*self.time_fn)();
+
+        // If the user specified a timeout, use that.
+        let timeout = match timeout {
+            Some(timeout) => timeout,
+            None => {
+                // Otherwise, use the expiration of the device code, which is relative to when the
+                // device authorization response was received rather than when polling starts. If
+                // the issuance time is unknown, polling starts the clock instead.
+                let issued_at = self.dev_auth_resp.issued_at().unwrap_or(now);
+                return self
+                    .dev_auth_resp
+                    .expires_at_from(issued_at)
+                    .ok_or_else(|| {
+                        RequestTokenError::Other("Failed to calculate timeout".to_string())
+                    });
             }
         };
+        let chrono_timeout = chrono::Duration::from_std(timeout)
+            .map_err(|_| RequestTokenError::Other("Failed to convert duration".to_string()))?;
 
-        // Explicitly process the response with a DeviceCodeErrorResponse
-        let res = endpoint_response::<RE, DeviceCodeErrorResponse, TR>(http_response);
-        match res {
-            // On a ServerResponse error, the error needs inspecting as a DeviceCodeErrorResponse
-            // to work out whether a retry needs to happen.
-            Err(RequestTokenError::ServerResponse(dcer)) => {
-                match dcer.error() {
-                    // On AuthorizationPending, a retry needs to happen with the same poll interval.
-                    DeviceCodeErrorResponseType::AuthorizationPending => {
-                        DeviceAccessTokenPollResult::ContinueWithNewPollInterval(current_interval)
+        // Calculate the DateTime at which the request times out.
+        now.checked_add_signed(chrono_timeout)
+            .ok_or_else(|| RequestTokenError::Other("Failed to calculate timeout".to_string()))
+    }
+}
+
+impl<'a, TR, TT, EF> DeviceAccessTokenRequest<'a, 'a, TR, TT, EF>
+where
+    TR: TokenResponse<TT> + 'a,
+    TT: TokenType + 'a,
+    EF: ExtraDeviceAuthorizationFields,
+{
+    ///
+    /// Asynchronously polls the authorization server, returning a stream of events that report
+    /// the progress of the flow.
+    ///
+    /// The stream ends after yielding [`DeviceAccessTokenEvent::Completed`],
+    /// [`DeviceAccessTokenEvent::Expired`] or [`DeviceAccessTokenEvent::Failed`]. Polling stops
+    /// as soon as the stream is dropped. To cancel the flow from elsewhere (e.g., a Ctrl-C
+    /// handler), wrap the stream using
+    /// [`futures::stream::abortable`](https://docs.rs/futures/0.3/futures/stream/fn.abortable.html)
+    /// and call `abort` on the returned handle.
+    ///
+    /// Requires "device-code-stream" feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use futures_util::stream::{abortable, StreamExt};
+    /// use oauth2::basic::BasicClient;
+    /// use oauth2::devicecode::{DeviceAccessTokenEvent, StandardDeviceAuthorizationResponse};
+    /// use oauth2::reqwest::async_http_client;
+    ///
+    /// # async fn err_wrapper(
+    /// #     client: BasicClient,
+    /// #     details: StandardDeviceAuthorizationResponse,
+    /// # ) -> Result<(), Box<dyn std::error::Error>> {
+    /// let (events, abort_handle) = abortable(
+    ///     client
+    ///         .exchange_device_access_token(&details)
+    ///         .request_stream(async_http_client, tokio::time::sleep, None),
+    /// );
+    /// // Call `abort_handle.abort()` to cancel the flow.
+    /// let mut events = Box::pin(events);
+    /// while let Some(event) = events.next().await {
+    ///     match event {
+    ///         DeviceAccessTokenEvent::Completed(token) => println!("Authorized: {:?}", token),
+    ///         DeviceAccessTokenEvent::Expired => println!("Code expired"),
+    ///         DeviceAccessTokenEvent::Failed(err) => println!("Failed: {}", err),
+    ///         DeviceAccessTokenEvent::TransientError(err, interval) => {
+    ///             println!("Retrying in {:?} after error: {}", interval, err)
+    ///         }
+    ///         _ => {}
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "device-code-stream")]
+    pub fn request_stream<C, F, S, SF, RE>(
+        self,
+        http_client: C,
+        sleep_fn: S,
+        timeout: Option<Duration>,
+    ) -> impl Stream<Item = DeviceAccessTokenEvent<TR, RE>> + 'a
+    where
+        C: Fn(HttpRequest) -> F + 'a,
+        F: Future<Output = Result<HttpResponse, RE>> + 'a,
+        S: Fn(Duration) -> SF + 'a,
+        SF: Future<Output = ()> + 'a,
+        RE: Error + 'static,
+    {
+        let poll_state = self.poll_state(timeout);
+        futures_util::stream::unfold(
+            Some((self, http_client, sleep_fn, poll_state, false)),
+            |stream_state| async move {
+                let (request, http_client, sleep_fn, poll_state, polled) = stream_state?;
+                let mut poll_state = match poll_state {
+                    Ok(poll_state) => poll_state,
+                    Err(err) => return Some((DeviceAccessTokenEvent::Failed(err.into()), None)),
+                };
+
+                if polled {
+                    sleep_fn(poll_state.interval()).await;
+                }
+                if poll_state.is_expired((*request.time_fn)()) {
+                    return Some((DeviceAccessTokenEvent::Expired, None));
+                }
+
+                let mut retried_client_secret = false;
+                let res = loop {
+                    let client_secret = match request.client_secret.resolve() {
+                        Ok(client_secret) => client_secret,
+                        Err(err) => {
+                            return Some((DeviceAccessTokenEvent::Failed(err.into()), None))
+                        }
+                    };
+                    let http_request = match request.prepare_request(client_secret.secret()) {
+                        Ok(http_request) => http_request,
+                        Err(err) => {
+                            return Some((DeviceAccessTokenEvent::Failed(err.into()), None))
+                        }
+                    };
+                    let res = http_client(http_request).await;
+
+                    // Retry once without waiting if the credential provider switched to a
+                    // different client secret after the current one was rejected.
+                    if let Ok(ref http_response) = res {
+                        if client_secret.check_response(http_response) && !retried_client_secret {
+                            retried_client_secret = true;
+                            continue;
+                        }
                     }
-                    // On SlowDown, a retry needs to happen with a larger poll interval.
-                    DeviceCodeErrorResponseType::SlowDown => {
-                        DeviceAccessTokenPollResult::ContinueWithNewPollInterval(
-                            current_interval + Duration::from_secs(5),
-                        )
+                    break res;
+                };
+
+                let event = match poll_state.process_response(res) {
+                    DeviceAccessTokenPollStatus::Pending(interval) => {
+                        DeviceAccessTokenEvent::Polling(interval)
+                    }
+                    DeviceAccessTokenPollStatus::SlowDown(interval) => {
+                        DeviceAccessTokenEvent::SlowDown(interval)
+                    }
+                    DeviceAccessTokenPollStatus::TransientError(err, interval) => {
+                        DeviceAccessTokenEvent::TransientError(err, interval)
+                    }
+                    DeviceAccessTokenPollStatus::Done(token) => {
+                        return Some((DeviceAccessTokenEvent::Completed(token), None))
+                    }
+                    DeviceAccessTokenPollStatus::Failed(err) => {
+                        return Some((DeviceAccessTokenEvent::Failed(err), None))
                     }
+                };
+                Some((
+                    event,
+                    Some((request, http_client, sleep_fn, Ok(poll_state), true)),
+                ))
+            },
+        )
+    }
+}
 
-                    // On any other error, just return the error.
-                    _ => DeviceAccessTokenPollResult::Done(
-                        Err(RequestTokenError::ServerResponse(dcer)),
-                        PhantomData,
-                    ),
-                }
-            }
+impl<'a, 'b, TR, TT, EF> EndpointRequest for DeviceAccessTokenRequest<'a, 'b, TR, TT, EF>
+where
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    EF: ExtraDeviceAuthorizationFields,
+{
+    type Output = TR;
+    type ErrorResponse = DeviceCodeErrorResponse;
 
-            // On any other success or failure, return the failure.
-            res => DeviceAccessTokenPollResult::Done(res, PhantomData),
-        }
+    fn prepare<RE>(&self) -> Result<HttpRequest, RequestTokenError<RE, DeviceCodeErrorResponse>>
+    where
+        RE: Error + 'static,
+    {
+        self.prepare_request(self.client_secret.resolve()?.secret())
     }
 
-    fn compute_timeout<RE>(
+    fn parse<RE>(
         &self,
-        timeout: Option<Duration>,
-    ) -> Result<DateTime<Utc>, RequestTokenError<RE, DeviceCodeErrorResponse>>
+        http_response: HttpResponse,
+    ) -> Result<TR, RequestTokenError<RE, DeviceCodeErrorResponse>>
     where
         RE: Error + 'static,
     {
-        // Calculate the request timeout - if the user specified a timeout,
-        // use that, otherwise use the value given by the device authorization
-        // response.
-        let timeout_dur = timeout.unwrap_or_else(|| self.dev_auth_resp.expires_in());
-        let chrono_timeout = chrono::Duration::from_std(timeout_dur)
-            .map_err(|_| RequestTokenError::Other("Failed to convert duration".to_string()))?;
-
-        // Calculate the DateTime at which the request times out.
-        let timeout_dt = (*self.time_fn)()
-            .checked_add_signed(chrono_timeout)
-            .ok_or_else(|| RequestTokenError::Other("Failed to calculate timeout".to_string()))?;
-
-        Ok(timeout_dt)
+        endpoint_response(http_response)
     }
 }
 
@@ -2634,6 +4133,49 @@ where
     }
 }
 
+///
+/// A token that can be introspected using an
+/// [RFC 7662](https://tools.ietf.org/html/rfc7662) compatible endpoint (see
+/// [`Client::introspect`]).
+///
+pub trait IntrospectableToken: Debug + Sync {
+    ///
+    /// The actual token value to be introspected.
+    ///
+    fn secret(&self) -> &str;
+
+    ///
+    /// Indicates the type of the token being introspected, as defined by
+    /// [RFC 7662, Section 2.1](https://tools.ietf.org/html/rfc7662#section-2.1).
+    ///
+    /// Values should be registered in the
+    /// [OAuth Token Type Hints Registry](https://tools.ietf.org/html/rfc7009#section-4.1.2) or
+    /// otherwise known to the target authorization servers. Returning `None` omits the
+    /// `token_type_hint` parameter.
+    ///
+    fn type_hint(&self) -> Option<&str>;
+}
+impl IntrospectableToken for AccessToken {
+    fn secret(&self) -> &str {
+        self.secret()
+    }
+
+    // Access tokens are introspected without a hint unless one is set explicitly via
+    // `IntrospectionRequest::set_token_type_hint`, matching earlier releases.
+    fn type_hint(&self) -> Option<&str> {
+        None
+    }
+}
+impl IntrospectableToken for RefreshToken {
+    fn secret(&self) -> &str {
+        self.secret()
+    }
+
+    fn type_hint(&self) -> Option<&str> {
+        Some("refresh_token")
+    }
+}
+
 ///
 /// Common methods shared by all OAuth2 token introspection implementations.
 ///
@@ -0,0 +1,917 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use http::status::StatusCode;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use url::{form_urlencoded, Url};
+
+use crate::basic::{BasicClient, BasicErrorResponseType};
+use crate::{
+    AuthUrl, ClientId, ClientSecret, CsrfToken, DeviceAuthorizationUrl, IntrospectionUrl,
+    RevocationUrl, TokenUrl,
+};
+
+const AUTHORIZE_PATH: &str = "/authorize";
+const TOKEN_PATH: &str = "/token";
+const INTROSPECTION_PATH: &str = "/introspect";
+const REVOCATION_PATH: &str = "/revoke";
+const DEVICE_AUTHORIZATION_PATH: &str = "/device_authorization";
+const DEVICE_VERIFICATION_PATH: &str = "/device";
+
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+// Largest request body the server accepts. Larger requests are answered with
+// `413 Payload Too Large` instead of allocating a buffer of the size claimed by the client.
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+///
+/// An endpoint served by [`MockServer`].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// Authorization endpoint ([RFC 6749](https://tools.ietf.org/html/rfc6749#section-3.1)).
+    Authorize,
+    /// Token endpoint ([RFC 6749](https://tools.ietf.org/html/rfc6749#section-3.2)).
+    Token,
+    /// Token introspection endpoint ([RFC 7662](https://tools.ietf.org/html/rfc7662)).
+    Introspection,
+    /// Token revocation endpoint ([RFC 7009](https://tools.ietf.org/html/rfc7009)).
+    Revocation,
+    /// Device authorization endpoint ([RFC 8628](https://tools.ietf.org/html/rfc8628)).
+    DeviceAuthorization,
+}
+
+///
+/// An in-process OAuth2 authorization server listening on a random localhost port.
+///
+/// The server implements the authorization, token, introspection, revocation and device
+/// authorization endpoints for a single registered client, and issues random codes and tokens.
+/// The authorization endpoint approves every valid request immediately by redirecting back to
+/// the client's redirect URI, while device authorization requests remain pending until approved
+/// or denied via [`MockServer::approve_device`] or [`MockServer::deny_device`].
+///
+/// Failures are scripted via [`MockServer::fail_next`] and [`MockServer::slow_down_next`]. The
+/// server shuts down when dropped.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # fn err_wrapper() -> Result<(), anyhow::Error> {
+/// use oauth2::basic::BasicErrorResponseType;
+/// use oauth2::mockserver::{Endpoint, MockServer};
+/// use oauth2::reqwest::http_client;
+///
+/// let server = MockServer::start()?;
+/// let client = server.basic_client();
+///
+/// server.fail_next(Endpoint::Token, BasicErrorResponseType::InvalidClient);
+/// assert!(client.exchange_client_credentials().request(http_client).is_err());
+/// assert!(client.exchange_client_credentials().request(http_client).is_ok());
+/// # Ok(())
+/// # }
+/// ```
+///
+pub struct MockServer {
+    address: SocketAddr,
+    state: Arc<Mutex<State>>,
+    shutdown: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+impl MockServer {
+    ///
+    /// Starts a server listening on a random localhost port.
+    ///
+    pub fn start() -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(State::new(address)));
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let thread_state = state.clone();
+        let thread_shutdown = shutdown.clone();
+        let thread = thread::Builder::new()
+            .name("oauth2-mock-server".to_string())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    if thread_shutdown.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        let state = thread_state.clone();
+                        thread::spawn(move || {
+                            // The client is gone if the connection fails, so there's nobody to
+                            // report the error to.
+                            let _ = serve(stream, &state);
+                        });
+                    }
+                }
+            })?;
+
+        Ok(MockServer {
+            address,
+            state,
+            shutdown,
+            thread: Some(thread),
+        })
+    }
+
+    ///
+    /// Returns the base URL of the server.
+    ///
+    pub fn url(&self) -> Url {
+        base_url(self.address)
+    }
+
+    ///
+    /// Returns the URL of the authorization endpoint.
+    ///
+    pub fn auth_url(&self) -> AuthUrl {
+        AuthUrl::from_url(self.endpoint_url(AUTHORIZE_PATH))
+    }
+
+    ///
+    /// Returns the URL of the token endpoint.
+    ///
+    pub fn token_url(&self) -> TokenUrl {
+        TokenUrl::from_url(self.endpoint_url(TOKEN_PATH))
+    }
+
+    ///
+    /// Returns the URL of the token introspection endpoint.
+    ///
+    pub fn introspection_url(&self) -> IntrospectionUrl {
+        IntrospectionUrl::from_url(self.endpoint_url(INTROSPECTION_PATH))
+    }
+
+    ///
+    /// Returns the URL of the token revocation endpoint.
+    ///
+    pub fn revocation_url(&self) -> RevocationUrl {
+        RevocationUrl::from_url(self.endpoint_url(REVOCATION_PATH))
+    }
+
+    ///
+    /// Returns the URL of the device authorization endpoint.
+    ///
+    pub fn device_authorization_url(&self) -> DeviceAuthorizationUrl {
+        DeviceAuthorizationUrl::from_url(self.endpoint_url(DEVICE_AUTHORIZATION_PATH))
+    }
+
+    ///
+    /// Returns the client ID registered with the server.
+    ///
+    pub fn client_id(&self) -> ClientId {
+        ClientId::new(self.state.lock().unwrap().client_id.clone())
+    }
+
+    ///
+    /// Returns the client secret registered with the server.
+    ///
+    pub fn client_secret(&self) -> ClientSecret {
+        ClientSecret::new(self.state.lock().unwrap().client_secret.clone())
+    }
+
+    ///
+    /// Returns a [`BasicClient`] configured with the registered client credentials and all of
+    /// the server's endpoints.
+    ///
+    pub fn basic_client(&self) -> BasicClient {
+        BasicClient::new(
+            self.client_id(),
+            Some(self.client_secret()),
+            self.auth_url(),
+            Some(self.token_url()),
+        )
+        .set_introspection_uri(self.introspection_url())
+        .set_revocation_uri(self.revocation_url())
+        .set_device_authorization_url(self.device_authorization_url())
+    }
+
+    ///
+    /// Adds a resource owner that may authenticate using the Resource Owner Password Credentials
+    /// grant.
+    ///
+    pub fn add_user(&self, username: &str, password: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .users
+            .insert(username.to_string(), password.to_string());
+    }
+
+    ///
+    /// Fails the next request to `endpoint` with the given error.
+    ///
+    /// Calling this method multiple times queues errors that are returned by consecutive
+    /// requests. Authorization endpoint errors are returned by redirecting to the client's
+    /// redirect URI.
+    ///
+    pub fn fail_next(&self, endpoint: Endpoint, error: BasicErrorResponseType) {
+        self.state
+            .lock()
+            .unwrap()
+            .failures
+            .entry(endpoint)
+            .or_default()
+            .push_back(error);
+    }
+
+    ///
+    /// Responds to the next `count` device access token requests with a `slow_down` error.
+    ///
+    pub fn slow_down_next(&self, count: usize) {
+        self.state.lock().unwrap().slow_downs += count;
+    }
+
+    ///
+    /// Enables or disables refresh token rotation (enabled by default).
+    ///
+    /// When enabled, refreshing an access token issues a new refresh token and revokes the one
+    /// used in the request.
+    ///
+    pub fn set_refresh_token_rotation(&self, enabled: bool) {
+        self.state.lock().unwrap().rotate_refresh_tokens = enabled;
+    }
+
+    ///
+    /// Sets the lifetime of authorization codes issued after this call (defaults to 10 minutes).
+    ///
+    pub fn set_code_lifetime(&self, lifetime: Duration) {
+        self.state.lock().unwrap().code_lifetime = lifetime;
+    }
+
+    ///
+    /// Sets the lifetime of access tokens issued after this call (defaults to 1 hour).
+    ///
+    pub fn set_access_token_lifetime(&self, lifetime: Duration) {
+        self.state.lock().unwrap().access_token_lifetime = lifetime;
+    }
+
+    ///
+    /// Sets the lifetime of device codes issued after this call (defaults to 10 minutes).
+    ///
+    pub fn set_device_code_lifetime(&self, lifetime: Duration) {
+        self.state.lock().unwrap().device_code_lifetime = lifetime;
+    }
+
+    ///
+    /// Sets the polling interval returned by the device authorization endpoint (defaults to 5
+    /// seconds).
+    ///
+    pub fn set_device_interval(&self, interval: Duration) {
+        self.state.lock().unwrap().device_interval = interval;
+    }
+
+    ///
+    /// Expires all authorization codes that have been issued but not yet exchanged.
+    ///
+    pub fn expire_codes(&self) {
+        let now = SystemTime::now();
+        for code in self.state.lock().unwrap().codes.values_mut() {
+            code.expires_at = now;
+        }
+    }
+
+    ///
+    /// Approves the pending device authorization identified by `user_code`.
+    ///
+    /// Returns `false` if no such authorization is pending.
+    ///
+    pub fn approve_device(&self, user_code: &str) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .set_device_status(user_code, DeviceStatus::Approved)
+    }
+
+    ///
+    /// Denies the pending device authorization identified by `user_code`.
+    ///
+    /// Returns `false` if no such authorization is pending.
+    ///
+    pub fn deny_device(&self, user_code: &str) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .set_device_status(user_code, DeviceStatus::Denied)
+    }
+
+    fn endpoint_url(&self, path: &str) -> Url {
+        self.url()
+            .join(path)
+            .expect("endpoint path should be valid")
+    }
+}
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // Wake up the accept loop so that it observes the shutdown flag.
+        let _ = TcpStream::connect(self.address);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn base_url(address: SocketAddr) -> Url {
+    Url::parse(&format!("http://{}", address)).expect("socket address should form a valid URL")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DeviceStatus {
+    Pending,
+    Approved,
+    Denied,
+}
+
+struct IssuedCode {
+    redirect_uri: Option<String>,
+    code_challenge: Option<(String, String)>,
+    scope: Option<String>,
+    expires_at: SystemTime,
+}
+
+struct IssuedToken {
+    scope: Option<String>,
+    expires_at: Option<SystemTime>,
+}
+
+struct DeviceGrant {
+    user_code: String,
+    scope: Option<String>,
+    expires_at: SystemTime,
+    status: DeviceStatus,
+}
+
+struct State {
+    address: SocketAddr,
+    client_id: String,
+    client_secret: String,
+    users: HashMap<String, String>,
+    failures: HashMap<Endpoint, VecDeque<BasicErrorResponseType>>,
+    slow_downs: usize,
+    rotate_refresh_tokens: bool,
+    code_lifetime: Duration,
+    access_token_lifetime: Duration,
+    device_code_lifetime: Duration,
+    device_interval: Duration,
+    codes: HashMap<String, IssuedCode>,
+    access_tokens: HashMap<String, IssuedToken>,
+    refresh_tokens: HashMap<String, IssuedToken>,
+    device_codes: HashMap<String, DeviceGrant>,
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    authorization: Option<String>,
+    form: HashMap<String, String>,
+}
+
+struct Response {
+    status: StatusCode,
+    headers: Vec<(&'static str, String)>,
+    body: String,
+}
+impl Response {
+    fn json(status: StatusCode, body: Value) -> Self {
+        Response {
+            status,
+            headers: vec![("Content-Type", "application/json".to_string())],
+            body: body.to_string(),
+        }
+    }
+
+    fn error(error: &str) -> Self {
+        if error == BasicErrorResponseType::InvalidClient.as_ref() {
+            let mut response = Self::json(StatusCode::UNAUTHORIZED, json!({ "error": error }));
+            response
+                .headers
+                .push(("WWW-Authenticate", "Basic realm=\"mock\"".to_string()));
+            response
+        } else {
+            Self::json(StatusCode::BAD_REQUEST, json!({ "error": error }))
+        }
+    }
+
+    fn redirect(location: Url) -> Self {
+        Response {
+            status: StatusCode::FOUND,
+            headers: vec![("Location", location.to_string())],
+            body: String::new(),
+        }
+    }
+
+    fn not_found() -> Self {
+        Response {
+            status: StatusCode::NOT_FOUND,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    fn payload_too_large() -> Self {
+        Response {
+            status: StatusCode::PAYLOAD_TOO_LARGE,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+}
+
+impl State {
+    fn new(address: SocketAddr) -> Self {
+        State {
+            address,
+            client_id: "mock_client".to_string(),
+            client_secret: random_value(),
+            users: HashMap::new(),
+            failures: HashMap::new(),
+            slow_downs: 0,
+            rotate_refresh_tokens: true,
+            code_lifetime: Duration::from_secs(600),
+            access_token_lifetime: Duration::from_secs(3600),
+            device_code_lifetime: Duration::from_secs(600),
+            device_interval: Duration::from_secs(5),
+            codes: HashMap::new(),
+            access_tokens: HashMap::new(),
+            refresh_tokens: HashMap::new(),
+            device_codes: HashMap::new(),
+        }
+    }
+
+    fn set_device_status(&mut self, user_code: &str, status: DeviceStatus) -> bool {
+        match self
+            .device_codes
+            .values_mut()
+            .find(|grant| grant.user_code == user_code && grant.status == DeviceStatus::Pending)
+        {
+            Some(grant) => {
+                grant.status = status;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn next_failure(&mut self, endpoint: Endpoint) -> Option<BasicErrorResponseType> {
+        self.failures
+            .get_mut(&endpoint)
+            .and_then(|failures| failures.pop_front())
+    }
+
+    fn handle(&mut self, request: &Request) -> Response {
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", AUTHORIZE_PATH) => self.authorize(request),
+            ("POST", TOKEN_PATH) => self.token(request),
+            ("POST", INTROSPECTION_PATH) => self.introspect(request),
+            ("POST", REVOCATION_PATH) => self.revoke(request),
+            ("POST", DEVICE_AUTHORIZATION_PATH) => self.device_authorization(request),
+            _ => Response::not_found(),
+        }
+    }
+
+    fn authorize(&mut self, request: &Request) -> Response {
+        let redirect_uri = match request.query.get("redirect_uri") {
+            Some(redirect_uri) => redirect_uri,
+            None => return Response::error(BasicErrorResponseType::InvalidRequest.as_ref()),
+        };
+        let mut location = match Url::parse(redirect_uri) {
+            Ok(location) => location,
+            Err(_) => return Response::error(BasicErrorResponseType::InvalidRequest.as_ref()),
+        };
+
+        let result = if let Some(error) = self.next_failure(Endpoint::Authorize) {
+            Err(error.as_ref().to_string())
+        } else if request.query.get("client_id") != Some(&self.client_id) {
+            Err(BasicErrorResponseType::UnauthorizedClient
+                .as_ref()
+                .to_string())
+        } else if request.query.get("response_type").map(String::as_str) != Some("code") {
+            Err("unsupported_response_type".to_string())
+        } else {
+            let code = random_value();
+            self.codes.insert(
+                code.clone(),
+                IssuedCode {
+                    redirect_uri: Some(redirect_uri.clone()),
+                    code_challenge: request.query.get("code_challenge").map(|challenge| {
+                        (
+                            challenge.clone(),
+                            request
+                                .query
+                                .get("code_challenge_method")
+                                .cloned()
+                                .unwrap_or_else(|| "plain".to_string()),
+                        )
+                    }),
+                    scope: request.query.get("scope").cloned(),
+                    expires_at: SystemTime::now() + self.code_lifetime,
+                },
+            );
+            Ok(code)
+        };
+
+        {
+            let mut query = location.query_pairs_mut();
+            match result {
+                Ok(code) => query.append_pair("code", &code),
+                Err(error) => query.append_pair("error", &error),
+            };
+            if let Some(state) = request.query.get("state") {
+                query.append_pair("state", state);
+            }
+        }
+        Response::redirect(location)
+    }
+
+    fn token(&mut self, request: &Request) -> Response {
+        if let Some(error) = self.next_failure(Endpoint::Token) {
+            return Response::error(error.as_ref());
+        }
+        if !self.authenticate_client(request) {
+            return Response::error(BasicErrorResponseType::InvalidClient.as_ref());
+        }
+
+        let grant_type = request.form.get("grant_type").map(String::as_str);
+        let result = match grant_type {
+            Some("authorization_code") => self.exchange_code(request),
+            Some("refresh_token") => self.exchange_refresh_token(request),
+            Some("password") => self.exchange_password(request),
+            Some("client_credentials") => Ok((request.form.get("scope").cloned(), false)),
+            Some(DEVICE_CODE_GRANT_TYPE) => self.exchange_device_code(request),
+            _ => Err(BasicErrorResponseType::UnsupportedGrantType
+                .as_ref()
+                .to_string()),
+        };
+
+        match result {
+            Ok((scope, issue_refresh_token)) => {
+                let access_token = random_value();
+                let expires_in = self.access_token_lifetime.as_secs();
+                self.access_tokens.insert(
+                    access_token.clone(),
+                    IssuedToken {
+                        scope: scope.clone(),
+                        expires_at: Some(SystemTime::now() + self.access_token_lifetime),
+                    },
+                );
+
+                let mut body = json!({
+                    "access_token": access_token,
+                    "token_type": "bearer",
+                    "expires_in": expires_in,
+                });
+                if let Some(scope) = scope.as_ref() {
+                    body["scope"] = json!(scope);
+                }
+                if issue_refresh_token {
+                    let refresh_token = match grant_type {
+                        Some("refresh_token") if !self.rotate_refresh_tokens => {
+                            request.form["refresh_token"].clone()
+                        }
+                        _ => {
+                            let refresh_token = random_value();
+                            self.refresh_tokens.insert(
+                                refresh_token.clone(),
+                                IssuedToken {
+                                    scope,
+                                    expires_at: None,
+                                },
+                            );
+                            refresh_token
+                        }
+                    };
+                    body["refresh_token"] = json!(refresh_token);
+                }
+                Response::json(StatusCode::OK, body)
+            }
+            Err(error) => Response::error(&error),
+        }
+    }
+
+    fn authenticate_client(&self, request: &Request) -> bool {
+        let (client_id, client_secret) = match request
+            .authorization
+            .as_ref()
+            .and_then(|authorization| parse_basic_auth(authorization))
+        {
+            Some(credentials) => credentials,
+            None => match (
+                request.form.get("client_id"),
+                request.form.get("client_secret"),
+            ) {
+                (Some(client_id), Some(client_secret)) => {
+                    (client_id.clone(), client_secret.clone())
+                }
+                _ => return false,
+            },
+        };
+        client_id == self.client_id && client_secret == self.client_secret
+    }
+
+    fn exchange_code(&mut self, request: &Request) -> Result<(Option<String>, bool), String> {
+        let invalid_grant = || BasicErrorResponseType::InvalidGrant.as_ref().to_string();
+
+        // Codes are single-use, even if the exchange fails.
+        let code = request
+            .form
+            .get("code")
+            .and_then(|code| self.codes.remove(code))
+            .ok_or_else(invalid_grant)?;
+        if code.expires_at <= SystemTime::now()
+            || request.form.get("redirect_uri") != code.redirect_uri.as_ref()
+        {
+            return Err(invalid_grant());
+        }
+
+        if let Some((challenge, method)) = code.code_challenge {
+            let verifier = request
+                .form
+                .get("code_verifier")
+                .ok_or_else(invalid_grant)?;
+            let computed = match method.as_str() {
+                "S256" => base64::encode_config(
+                    Sha256::digest(verifier.as_bytes()),
+                    base64::URL_SAFE_NO_PAD,
+                ),
+                "plain" => verifier.clone(),
+                _ => return Err(invalid_grant()),
+            };
+            if computed != challenge {
+                return Err(invalid_grant());
+            }
+        }
+
+        Ok((code.scope, true))
+    }
+
+    fn exchange_refresh_token(
+        &mut self,
+        request: &Request,
+    ) -> Result<(Option<String>, bool), String> {
+        let refresh_token = request
+            .form
+            .get("refresh_token")
+            .ok_or_else(|| BasicErrorResponseType::InvalidRequest.as_ref().to_string())?;
+        let scope = if self.rotate_refresh_tokens {
+            self.refresh_tokens
+                .remove(refresh_token)
+                .map(|token| token.scope)
+        } else {
+            self.refresh_tokens
+                .get(refresh_token)
+                .map(|token| token.scope.clone())
+        }
+        .ok_or_else(|| BasicErrorResponseType::InvalidGrant.as_ref().to_string())?;
+
+        Ok((request.form.get("scope").cloned().or(scope), true))
+    }
+
+    fn exchange_password(&mut self, request: &Request) -> Result<(Option<String>, bool), String> {
+        match (request.form.get("username"), request.form.get("password")) {
+            (Some(username), Some(password)) if self.users.get(username) == Some(password) => {
+                Ok((request.form.get("scope").cloned(), true))
+            }
+            _ => Err(BasicErrorResponseType::InvalidGrant.as_ref().to_string()),
+        }
+    }
+
+    fn exchange_device_code(
+        &mut self,
+        request: &Request,
+    ) -> Result<(Option<String>, bool), String> {
+        let device_code = request
+            .form
+            .get("device_code")
+            .ok_or_else(|| BasicErrorResponseType::InvalidRequest.as_ref().to_string())?;
+        let grant = self
+            .device_codes
+            .get(device_code)
+            .ok_or_else(|| BasicErrorResponseType::InvalidGrant.as_ref().to_string())?;
+
+        if grant.expires_at <= SystemTime::now() {
+            self.device_codes.remove(device_code);
+            return Err("expired_token".to_string());
+        }
+        if self.slow_downs > 0 {
+            self.slow_downs -= 1;
+            return Err("slow_down".to_string());
+        }
+        match grant.status {
+            DeviceStatus::Pending => Err("authorization_pending".to_string()),
+            DeviceStatus::Denied => {
+                self.device_codes.remove(device_code);
+                Err("access_denied".to_string())
+            }
+            DeviceStatus::Approved => {
+                let scope = grant.scope.clone();
+                self.device_codes.remove(device_code);
+                Ok((scope, true))
+            }
+        }
+    }
+
+    fn introspect(&mut self, request: &Request) -> Response {
+        if let Some(error) = self.next_failure(Endpoint::Introspection) {
+            return Response::error(error.as_ref());
+        }
+        if !self.authenticate_client(request) {
+            return Response::error(BasicErrorResponseType::InvalidClient.as_ref());
+        }
+        let token = match request.form.get("token") {
+            Some(token) => token,
+            None => return Response::error(BasicErrorResponseType::InvalidRequest.as_ref()),
+        };
+
+        let issued = match request.form.get("token_type_hint").map(String::as_str) {
+            Some("refresh_token") => self
+                .refresh_tokens
+                .get(token)
+                .or_else(|| self.access_tokens.get(token)),
+            _ => self
+                .access_tokens
+                .get(token)
+                .or_else(|| self.refresh_tokens.get(token)),
+        };
+        let now = SystemTime::now();
+        let body = match issued {
+            Some(issued) if issued.expires_at.map(|exp| exp > now).unwrap_or(true) => {
+                let mut body = json!({
+                    "active": true,
+                    "client_id": self.client_id,
+                    "token_type": "bearer",
+                });
+                if let Some(scope) = issued.scope.as_ref() {
+                    body["scope"] = json!(scope);
+                }
+                if let Some(expires_at) = issued.expires_at {
+                    body["exp"] = json!(unix_timestamp(expires_at));
+                }
+                body
+            }
+            _ => json!({ "active": false }),
+        };
+        Response::json(StatusCode::OK, body)
+    }
+
+    fn revoke(&mut self, request: &Request) -> Response {
+        if let Some(error) = self.next_failure(Endpoint::Revocation) {
+            return Response::error(error.as_ref());
+        }
+        if !self.authenticate_client(request) {
+            return Response::error(BasicErrorResponseType::InvalidClient.as_ref());
+        }
+        let token = match request.form.get("token") {
+            Some(token) => token,
+            None => return Response::error(BasicErrorResponseType::InvalidRequest.as_ref()),
+        };
+
+        // Revoking an unknown token succeeds, as required by RFC 7009.
+        self.access_tokens.remove(token);
+        self.refresh_tokens.remove(token);
+        Response {
+            status: StatusCode::OK,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    fn device_authorization(&mut self, request: &Request) -> Response {
+        if let Some(error) = self.next_failure(Endpoint::DeviceAuthorization) {
+            return Response::error(error.as_ref());
+        }
+        if !self.authenticate_client(request) {
+            return Response::error(BasicErrorResponseType::InvalidClient.as_ref());
+        }
+
+        let device_code = random_value();
+        let user_code = random_value()[..8].to_uppercase();
+        self.device_codes.insert(
+            device_code.clone(),
+            DeviceGrant {
+                user_code: user_code.clone(),
+                scope: request.form.get("scope").cloned(),
+                expires_at: SystemTime::now() + self.device_code_lifetime,
+                status: DeviceStatus::Pending,
+            },
+        );
+
+        Response::json(
+            StatusCode::OK,
+            json!({
+                "device_code": device_code,
+                "user_code": user_code,
+                "verification_uri": base_url(self.address)
+                    .join(DEVICE_VERIFICATION_PATH)
+                    .expect("endpoint path should be valid")
+                    .to_string(),
+                "expires_in": self.device_code_lifetime.as_secs(),
+                "interval": self.device_interval.as_secs(),
+            }),
+        )
+    }
+}
+
+fn random_value() -> String {
+    CsrfToken::new_random_len(24).secret().clone()
+}
+
+fn unix_timestamp(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+fn parse_basic_auth(authorization: &str) -> Option<(String, String)> {
+    let encoded = authorization.strip_prefix("Basic ")?;
+    let decoded = String::from_utf8(base64::decode(encoded).ok()?).ok()?;
+    let mut parts = decoded.splitn(2, ':');
+    // The client ID and secret are form-urlencoded before being combined (see Section 2.3.1 of
+    // RFC 6749).
+    let mut decode = || {
+        form_urlencoded::parse(parts.next()?.as_bytes())
+            .next()
+            .map(|(value, _)| value.into_owned())
+            .or_else(|| Some(String::new()))
+    };
+    Some((decode()?, decode()?))
+}
+
+fn parse_params(input: &[u8]) -> HashMap<String, String> {
+    form_urlencoded::parse(input)
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect()
+}
+
+fn serve(stream: TcpStream, state: &Mutex<State>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let target = parts.next().unwrap_or("/").to_string();
+
+    let mut content_length = 0;
+    let mut authorization = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let mut header = line.splitn(2, ':');
+        if let (Some(name), Some(value)) = (header.next(), header.next()) {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.parse().unwrap_or(0);
+            } else if name.eq_ignore_ascii_case("authorization") {
+                authorization = Some(value.to_string());
+            }
+        }
+    }
+
+    let response = if content_length > MAX_BODY_SIZE {
+        Response::payload_too_large()
+    } else {
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+
+        let mut target = target.splitn(2, '?');
+        let path = target.next().unwrap_or("/").to_string();
+        let query = parse_params(target.next().unwrap_or("").as_bytes());
+        let request = Request {
+            method,
+            path,
+            query,
+            authorization,
+            form: parse_params(&body),
+        };
+        state.lock().unwrap().handle(&request)
+    };
+
+    let mut writer = stream;
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status.as_u16(),
+        response.status.canonical_reason().unwrap_or(""),
+        response.body.len()
+    )?;
+    for (name, value) in &response.headers {
+        write!(writer, "{}: {}\r\n", name, value)?;
+    }
+    write!(writer, "\r\n{}", response.body)?;
+    writer.flush()
+}
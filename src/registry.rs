@@ -0,0 +1,422 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::basic::{BasicClient, BasicClientBuilder};
+use crate::{
+    AuthType, AuthUrl, ClientId, ClientSecret, ConfigurationError, DeviceAuthorizationUrl,
+    IntrospectionUrl, RevocationUrl, Scope, TokenUrl,
+};
+
+///
+/// Error encountered while loading a [`ClientRegistry`].
+///
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum RegistryError {
+    ///
+    /// Failed to read a configuration or secret file.
+    ///
+    #[error("Failed to read {}", _0.display())]
+    Io(PathBuf, #[source] io::Error),
+    ///
+    /// The configuration file format is not supported, or the crate feature required to parse
+    /// it is not enabled.
+    ///
+    #[error("Unsupported configuration file format: {0}")]
+    UnsupportedFormat(String),
+    ///
+    /// Failed to parse the configuration.
+    ///
+    #[error("Failed to parse configuration")]
+    Parse(#[source] Box<dyn std::error::Error + Send + Sync>),
+    ///
+    /// An environment variable referenced by the configuration is not set.
+    ///
+    #[error("Environment variable `{0}` referenced by the configuration is not set")]
+    MissingEnvVar(String),
+    ///
+    /// A configuration value is invalid.
+    ///
+    #[error("Invalid `{field}` for provider `{provider}` of tenant `{tenant}`: {message}")]
+    InvalidValue {
+        ///
+        /// Tenant whose configuration is invalid.
+        ///
+        tenant: String,
+        ///
+        /// Provider whose configuration is invalid.
+        ///
+        provider: String,
+        ///
+        /// Name of the invalid field.
+        ///
+        field: &'static str,
+        ///
+        /// Description of the problem.
+        ///
+        message: String,
+    },
+    ///
+    /// The resulting client configuration is invalid (see [`ClientBuilder`](crate::ClientBuilder)).
+    ///
+    #[error("Invalid configuration for provider `{provider}` of tenant `{tenant}`")]
+    Configuration {
+        ///
+        /// Tenant whose configuration is invalid.
+        ///
+        tenant: String,
+        ///
+        /// Provider whose configuration is invalid.
+        ///
+        provider: String,
+        ///
+        /// Underlying configuration error.
+        ///
+        #[source]
+        source: ConfigurationError,
+    },
+}
+
+///
+/// Source of a client secret: either a literal value (which may reference environment
+/// variables), an environment variable, or a file (e.g., a mounted container secret).
+///
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SecretSource {
+    Value(String),
+    Env { env: String },
+    File { file: PathBuf },
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ConfiguredAuthType {
+    BasicAuth,
+    RequestBody,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProviderConfig {
+    client_id: String,
+    client_secret: Option<SecretSource>,
+    auth_url: String,
+    token_url: String,
+    introspection_url: Option<String>,
+    revocation_url: Option<String>,
+    device_authorization_url: Option<String>,
+    auth_type: Option<ConfiguredAuthType>,
+    #[serde(default)]
+    scopes: Vec<String>,
+}
+
+///
+/// Client loaded from a [`ClientRegistry`], along with its configured default scopes.
+///
+#[derive(Clone, Debug)]
+pub struct RegisteredClient {
+    client: BasicClient,
+    default_scopes: Vec<Scope>,
+}
+impl RegisteredClient {
+    ///
+    /// Returns the configured client.
+    ///
+    pub fn client(&self) -> &BasicClient {
+        &self.client
+    }
+
+    ///
+    /// Returns the scopes configured for this provider, which callers would typically request
+    /// by default (e.g., using `add_scopes`).
+    ///
+    pub fn default_scopes(&self) -> &[Scope] {
+        &self.default_scopes
+    }
+}
+
+///
+/// Registry of [`BasicClient`]s for multiple tenants and providers, loaded from a configuration
+/// file.
+///
+/// The configuration maps each tenant to its providers, and each provider to its client
+/// configuration. JSON is always supported, while TOML and YAML require the `toml` and `yaml`
+/// features, respectively. For example, in TOML:
+///
+/// ```toml
+/// [acme.google]
+/// client_id = "acme-client"
+/// client_secret = "${ACME_GOOGLE_SECRET}"
+/// auth_url = "https://accounts.google.com/o/oauth2/v2/auth"
+/// token_url = "https://oauth2.googleapis.com/token"
+/// revocation_url = "https://oauth2.googleapis.com/revoke"
+/// scopes = ["openid", "email"]
+///
+/// [acme.github]
+/// client_id = "acme-github"
+/// client_secret = { file = "/run/secrets/acme-github" }
+/// auth_url = "https://github.com/login/oauth/authorize"
+/// token_url = "https://github.com/login/oauth/access_token"
+/// auth_type = "request_body"
+/// ```
+///
+/// Each provider supports the following fields:
+///  * `client_id` (required)
+///  * `client_secret`: either a string, `{ env = "VARIABLE" }`, or `{ file = "/path" }`. Trailing
+///    newlines are removed from secrets read from files.
+///  * `auth_url` and `token_url` (required)
+///  * `introspection_url`, `revocation_url` and `device_authorization_url`
+///  * `auth_type`: either `"basic_auth"` (the default) or `"request_body"`
+///  * `scopes`: default scopes (see [`RegisteredClient::default_scopes`])
+///
+/// String values may reference environment variables as `${VARIABLE}`, and `$$` denotes a
+/// literal `$`. Clients are validated using [`ClientBuilder`](crate::ClientBuilder) when the
+/// registry is loaded.
+///
+#[derive(Clone, Debug, Default)]
+pub struct ClientRegistry {
+    tenants: HashMap<String, HashMap<String, RegisteredClient>>,
+}
+impl ClientRegistry {
+    ///
+    /// Loads a registry from a file, whose format is determined by its extension (`.json`,
+    /// `.toml`, `.yaml` or `.yml`).
+    ///
+    pub fn from_file<P>(path: P) -> Result<Self, RegistryError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let contents =
+            fs::read_to_string(path).map_err(|err| RegistryError::Io(path.to_owned(), err))?;
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+        match extension {
+            "json" => Self::from_json_str(&contents),
+            #[cfg(feature = "toml")]
+            "toml" => Self::from_toml_str(&contents),
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => Self::from_yaml_str(&contents),
+            _ => Err(RegistryError::UnsupportedFormat(extension.to_string())),
+        }
+    }
+
+    ///
+    /// Loads a registry from a JSON string.
+    ///
+    pub fn from_json_str(config: &str) -> Result<Self, RegistryError> {
+        Self::from_config(
+            serde_json::from_str(config).map_err(|err| RegistryError::Parse(Box::new(err)))?,
+        )
+    }
+
+    ///
+    /// Loads a registry from a TOML string. Requires the `toml` feature.
+    ///
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(config: &str) -> Result<Self, RegistryError> {
+        Self::from_config(
+            toml::from_str(config).map_err(|err| RegistryError::Parse(Box::new(err)))?,
+        )
+    }
+
+    ///
+    /// Loads a registry from a YAML string. Requires the `yaml` feature.
+    ///
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml_str(config: &str) -> Result<Self, RegistryError> {
+        Self::from_config(
+            serde_yaml::from_str(config).map_err(|err| RegistryError::Parse(Box::new(err)))?,
+        )
+    }
+
+    ///
+    /// Returns the client configured for the given tenant and provider, if any.
+    ///
+    pub fn get(&self, tenant: &str, provider: &str) -> Option<&RegisteredClient> {
+        self.tenants
+            .get(tenant)
+            .and_then(|providers| providers.get(provider))
+    }
+
+    ///
+    /// Returns the [`BasicClient`] configured for the given tenant and provider, if any.
+    ///
+    pub fn client(&self, tenant: &str, provider: &str) -> Option<&BasicClient> {
+        self.get(tenant, provider).map(RegisteredClient::client)
+    }
+
+    ///
+    /// Returns the names of the configured tenants.
+    ///
+    pub fn tenants(&self) -> impl Iterator<Item = &str> {
+        self.tenants.keys().map(String::as_str)
+    }
+
+    ///
+    /// Returns the names of the providers configured for the given tenant.
+    ///
+    pub fn providers<'a>(&'a self, tenant: &str) -> impl Iterator<Item = &'a str> {
+        self.tenants
+            .get(tenant)
+            .into_iter()
+            .flat_map(|providers| providers.keys().map(String::as_str))
+    }
+
+    fn from_config(
+        config: HashMap<String, HashMap<String, ProviderConfig>>,
+    ) -> Result<Self, RegistryError> {
+        let mut tenants = HashMap::new();
+        for (tenant, providers) in config {
+            let mut clients = HashMap::new();
+            for (provider, provider_config) in providers {
+                let client = build_client(&tenant, &provider, provider_config)?;
+                clients.insert(provider, client);
+            }
+            tenants.insert(tenant, clients);
+        }
+        Ok(ClientRegistry { tenants })
+    }
+}
+
+fn build_client(
+    tenant: &str,
+    provider: &str,
+    config: ProviderConfig,
+) -> Result<RegisteredClient, RegistryError> {
+    let invalid = |field: &'static str, message: String| RegistryError::InvalidValue {
+        tenant: tenant.to_string(),
+        provider: provider.to_string(),
+        field,
+        message,
+    };
+    let env_var = |name: &str| std::env::var(name).ok();
+    let interpolate = |field: &'static str, value: &str| {
+        interpolate(value, env_var).map_err(|err| match err {
+            InterpolationError::Unterminated(offset) => invalid(
+                field,
+                format!("unterminated `${{` at byte offset {}", offset),
+            ),
+            InterpolationError::MissingEnvVar(name) => RegistryError::MissingEnvVar(name),
+        })
+    };
+
+    let mut builder =
+        BasicClientBuilder::new(ClientId::new(interpolate("client_id", &config.client_id)?))
+            .set_auth_uri(
+                AuthUrl::new(interpolate("auth_url", &config.auth_url)?)
+                    .map_err(|err| invalid("auth_url", err.to_string()))?,
+            )
+            .set_token_uri(
+                TokenUrl::new(interpolate("token_url", &config.token_url)?)
+                    .map_err(|err| invalid("token_url", err.to_string()))?,
+            );
+
+    if let Some(client_secret) = config.client_secret {
+        let client_secret = match client_secret {
+            SecretSource::Value(value) => interpolate("client_secret", &value)?,
+            SecretSource::Env { env } => {
+                env_var(&env).ok_or_else(|| RegistryError::MissingEnvVar(env.clone()))?
+            }
+            SecretSource::File { file } => fs::read_to_string(&file)
+                .map_err(|err| RegistryError::Io(file.clone(), err))?
+                .trim_end_matches(&['\n', '\r'][..])
+                .to_string(),
+        };
+        builder = builder.set_client_secret(ClientSecret::new(client_secret));
+    }
+    if let Some(ref introspection_url) = config.introspection_url {
+        builder = builder.set_introspection_uri(
+            IntrospectionUrl::new(interpolate("introspection_url", introspection_url)?)
+                .map_err(|err| invalid("introspection_url", err.to_string()))?,
+        );
+    }
+    if let Some(ref revocation_url) = config.revocation_url {
+        builder = builder.set_revocation_uri(
+            RevocationUrl::new(interpolate("revocation_url", revocation_url)?)
+                .map_err(|err| invalid("revocation_url", err.to_string()))?,
+        );
+    }
+    if let Some(ref device_authorization_url) = config.device_authorization_url {
+        builder = builder.set_device_authorization_url(
+            DeviceAuthorizationUrl::new(interpolate(
+                "device_authorization_url",
+                device_authorization_url,
+            )?)
+            .map_err(|err| invalid("device_authorization_url", err.to_string()))?,
+        );
+    }
+    if let Some(auth_type) = config.auth_type {
+        builder = builder.set_auth_type(match auth_type {
+            ConfiguredAuthType::BasicAuth => AuthType::BasicAuth,
+            ConfiguredAuthType::RequestBody => AuthType::RequestBody,
+        });
+    }
+
+    let client = builder
+        .build()
+        .map_err(|source| RegistryError::Configuration {
+            tenant: tenant.to_string(),
+            provider: provider.to_string(),
+            source,
+        })?;
+    let default_scopes = config
+        .scopes
+        .iter()
+        .map(|scope| interpolate("scopes", scope).map(Scope::new))
+        .collect::<Result<_, _>>()?;
+
+    Ok(RegisteredClient {
+        client,
+        default_scopes,
+    })
+}
+
+enum InterpolationError {
+    Unterminated(usize),
+    MissingEnvVar(String),
+}
+
+///
+/// Replaces each `${VARIABLE}` in `value` with the value returned by `env_var`, and each `$$`
+/// with `$`.
+///
+/// Errors never include `value`, which may be a client secret.
+///
+fn interpolate<E>(value: &str, env_var: E) -> Result<String, InterpolationError>
+where
+    E: Fn(&str) -> Option<String>,
+{
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(index) = rest.find('$') {
+        result.push_str(&rest[..index]);
+        rest = &rest[index..];
+        if rest.starts_with("$$") {
+            result.push('$');
+            rest = &rest[2..];
+        } else if rest.starts_with("${") {
+            let end = rest
+                .find('}')
+                .ok_or_else(|| InterpolationError::Unterminated(value.len() - rest.len()))?;
+            let name = &rest[2..end];
+            result.push_str(
+                &env_var(name)
+                    .ok_or_else(|| InterpolationError::MissingEnvVar(name.to_string()))?,
+            );
+            rest = &rest[end + 1..];
+        } else {
+            result.push('$');
+            rest = &rest[1..];
+        }
+    }
+    result.push_str(rest);
+    Ok(result)
+}
@@ -22,6 +22,14 @@ where
     Other(String),
 }
 
+///
+/// [reqwest-middleware](https://crates.io/crates/reqwest-middleware) integration for authorizing
+/// outgoing requests with access tokens. Requires "reqwest-middleware" feature and Rust 1.54 or
+/// newer.
+///
+#[cfg(feature = "reqwest-middleware")]
+pub mod middleware;
+
 #[cfg(not(target_arch = "wasm32"))]
 pub use blocking::http_client;
 ///
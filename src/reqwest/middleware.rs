@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use http::header::AUTHORIZATION;
+use http::status::StatusCode;
+use reqwest::{Request, Response};
+use reqwest_middleware::{Error, Middleware, Next, Result};
+use task_local_extensions::Extensions;
+
+use crate::basic::BasicTokenType;
+use crate::bearer::authorization_header_value;
+use crate::tokensource::{is_invalid_token_challenge, TokenSource};
+use crate::AccessToken;
+
+pub use reqwest_middleware;
+
+///
+/// A [`Middleware`] that sets the `Authorization: Bearer` header
+/// ([RFC 6750](https://tools.ietf.org/html/rfc6750#section-2.1)) on each outgoing request.
+///
+/// Access tokens are obtained from a [`TokenSource`], which caches them until they expire. If the
+/// server responds with `401 Unauthorized` and a `WWW-Authenticate` challenge indicating an
+/// `invalid_token` error, the token is invalidated and the request is retried once with a newly
+/// obtained token. Requests with streaming bodies cannot be cloned and are therefore never
+/// retried.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use oauth2::basic::BasicClient;
+/// # async fn err_wrapper(client: BasicClient) -> Result<(), anyhow::Error> {
+/// use oauth2::reqwest::async_http_client;
+/// use oauth2::reqwest::middleware::{reqwest_middleware, BearerTokenMiddleware};
+/// use oauth2::tokensource::ClientCredentialsTokenSource;
+///
+/// let http_client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+///     .with(BearerTokenMiddleware::new(ClientCredentialsTokenSource::new(
+///         client,
+///         async_http_client,
+///     )))
+///     .build();
+///
+/// let response = http_client
+///     .get("https://api.example.com/resource")
+///     .send()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+pub struct BearerTokenMiddleware<S> {
+    token_source: Arc<S>,
+}
+impl<S> BearerTokenMiddleware<S> {
+    ///
+    /// Creates a middleware that authorizes requests with tokens obtained from `token_source`.
+    ///
+    pub fn new(token_source: S) -> Self {
+        Self::from_arc(Arc::new(token_source))
+    }
+
+    ///
+    /// Creates a middleware that authorizes requests with tokens obtained from a shared
+    /// `token_source`.
+    ///
+    pub fn from_arc(token_source: Arc<S>) -> Self {
+        BearerTokenMiddleware { token_source }
+    }
+}
+impl<S> Clone for BearerTokenMiddleware<S> {
+    fn clone(&self) -> Self {
+        Self::from_arc(self.token_source.clone())
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl<S> Middleware for BearerTokenMiddleware<S>
+where
+    S: TokenSource + 'static,
+{
+    async fn handle(
+        &self,
+        request: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let retry_request = request.try_clone();
+
+        let token = self.token_source.token().await.map_err(Error::middleware)?;
+        let response = next
+            .clone()
+            .run(authorize(request, &token)?, extensions)
+            .await?;
+
+        let retry_request = match retry_request {
+            Some(retry_request)
+                if response.status() == StatusCode::UNAUTHORIZED
+                    && is_invalid_token_challenge(response.headers()) =>
+            {
+                retry_request
+            }
+            _ => return Ok(response),
+        };
+
+        self.token_source.invalidate(&token);
+        let token = self.token_source.token().await.map_err(Error::middleware)?;
+        next.run(authorize(retry_request, &token)?, extensions)
+            .await
+    }
+}
+
+fn authorize(mut request: Request, token: &AccessToken) -> Result<Request> {
+    let value =
+        authorization_header_value(token, &BasicTokenType::Bearer).map_err(Error::middleware)?;
+    request.headers_mut().insert(AUTHORIZATION, value);
+    Ok(request)
+}
@@ -0,0 +1,735 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use http::header::{HeaderValue, AUTHORIZATION, CACHE_CONTROL, CONTENT_TYPE, WWW_AUTHENTICATE};
+use http::method::Method;
+use http::status::StatusCode;
+use sha2::{Digest, Sha256};
+use url::form_urlencoded;
+
+use crate::bearer::{BearerErrorResponseType, Challenge};
+use crate::{
+    AccessToken, Client, ConfigurationError, ErrorResponse, HttpRequest, HttpResponse,
+    RequestTokenError, RevocableToken, Scope, TokenIntrospectionResponse, TokenResponse, TokenType,
+};
+
+const ACCESS_TOKEN_PARAM: &str = "access_token";
+
+///
+/// Error encountered while extracting a bearer token from a request.
+///
+/// Each of these errors corresponds to an `invalid_request` error as defined in
+/// [Section 3.1 of RFC 6750](https://tools.ietf.org/html/rfc6750#section-3.1). Use
+/// [`ExtractTokenError::to_challenge`] to build the corresponding `WWW-Authenticate` challenge.
+///
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ExtractTokenError {
+    /// The request includes an access token using more than one method.
+    #[error("Request includes an access token using more than one method")]
+    MultipleMethods,
+    /// The request includes more than one access token using the same method.
+    #[error("Request includes more than one access token")]
+    MultipleTokens,
+    /// The `Authorization` header includes a malformed bearer token.
+    #[error("Malformed bearer token")]
+    MalformedToken,
+}
+impl ExtractTokenError {
+    ///
+    /// Returns a `Bearer` challenge with an `invalid_request` error describing this error.
+    ///
+    pub fn to_challenge(&self) -> Challenge {
+        Challenge::bearer()
+            .set_error(BearerErrorResponseType::InvalidRequest)
+            .set_error_description(&self.to_string())
+    }
+}
+
+///
+/// Extracts the bearer token that a client sent with `request` using the `Authorization` request
+/// header or the `access_token` URI query parameter
+/// ([Section 2 of RFC 6750](https://tools.ietf.org/html/rfc6750#section-2)).
+///
+/// Returns `Ok(None)` if the request does not include a bearer token, in which case the resource
+/// server should respond with a `Bearer` challenge that has no error code. Requests that include
+/// more than one token are rejected, as required by RFC 6750.
+///
+/// This function does not inspect the request body. Use [`extract_bearer_token_with_form_body`]
+/// to also accept tokens sent as form-encoded body parameters.
+///
+/// # Example
+///
+/// ```rust
+/// use oauth2::bearer::{BearerErrorResponseType, Challenge};
+/// use oauth2::http::header::AUTHORIZATION;
+/// use oauth2::http::{Request, Response, StatusCode};
+/// use oauth2::resource::{challenge_response, extract_bearer_token};
+///
+/// fn handle(request: Request<Vec<u8>>) -> Response<Vec<u8>> {
+///     let token = match extract_bearer_token(&request) {
+///         Ok(Some(token)) => token,
+///         Ok(None) => {
+///             return challenge_response(&Challenge::bearer().set_realm("example")).unwrap()
+///         }
+///         Err(err) => return challenge_response(&err.to_challenge()).unwrap(),
+///     };
+///     // Validate the token (e.g., using `Client::introspect`) before handling the request.
+///     if token.secret() != "mF_9.B5f-4.1JqM" {
+///         return challenge_response(
+///             &Challenge::bearer().set_error(BearerErrorResponseType::InvalidToken),
+///         )
+///         .unwrap();
+///     }
+///     Response::new(b"protected resource".to_vec())
+/// }
+///
+/// let request = Request::get("https://api.example.com/resource")
+///     .header(AUTHORIZATION, "Bearer mF_9.B5f-4.1JqM")
+///     .body(Vec::new())
+///     .unwrap();
+/// assert_eq!(StatusCode::OK, handle(request).status());
+///
+/// let request = Request::get("https://api.example.com/resource?access_token=a&access_token=b")
+///     .body(Vec::new())
+///     .unwrap();
+/// assert_eq!(StatusCode::BAD_REQUEST, handle(request).status());
+///
+/// let request = Request::get("https://api.example.com/resource")
+///     .body(Vec::new())
+///     .unwrap();
+/// assert_eq!(StatusCode::UNAUTHORIZED, handle(request).status());
+/// ```
+///
+pub fn extract_bearer_token<B>(
+    request: &http::Request<B>,
+) -> Result<Option<AccessToken>, ExtractTokenError> {
+    let header_token = header_token(request)?;
+    let query_token = query_token(request)?;
+    combine(header_token, query_token)
+}
+
+///
+/// Extracts the bearer token that a client sent with `request` using the `Authorization` request
+/// header, the `access_token` form-encoded body parameter, or the `access_token` URI query
+/// parameter ([Section 2 of RFC 6750](https://tools.ietf.org/html/rfc6750#section-2)).
+///
+/// The body is only inspected if the request method is not `GET` and its content type is
+/// `application/x-www-form-urlencoded`. See [`extract_bearer_token`] for details.
+///
+pub fn extract_bearer_token_with_form_body<B>(
+    request: &http::Request<B>,
+) -> Result<Option<AccessToken>, ExtractTokenError>
+where
+    B: AsRef<[u8]>,
+{
+    let token = extract_bearer_token(request)?;
+    let form_token = if is_form_request(request) {
+        single_param(request.body().as_ref())?
+    } else {
+        None
+    };
+    combine(token, form_token)
+}
+
+fn combine(
+    first: Option<AccessToken>,
+    second: Option<AccessToken>,
+) -> Result<Option<AccessToken>, ExtractTokenError> {
+    match (first, second) {
+        (Some(_), Some(_)) => Err(ExtractTokenError::MultipleMethods),
+        (first, second) => Ok(first.or(second)),
+    }
+}
+
+fn header_token<B>(request: &http::Request<B>) -> Result<Option<AccessToken>, ExtractTokenError> {
+    let mut token = None;
+    for value in request.headers().get_all(AUTHORIZATION) {
+        let value = value
+            .to_str()
+            .map_err(|_| ExtractTokenError::MalformedToken)?;
+        let mut parts = value.trim().splitn(2, ' ');
+        let scheme = parts.next().unwrap_or("");
+        if !scheme.eq_ignore_ascii_case("bearer") {
+            // Credentials for other authentication schemes are not bearer tokens.
+            continue;
+        }
+
+        // b64token = 1*( ALPHA / DIGIT / "-" / "." / "_" / "~" / "+" / "/" ) *"="
+        let credentials = parts.next().unwrap_or("").trim_start();
+        let (chars, padding) = credentials.split_at(credentials.trim_end_matches('=').len());
+        if chars.is_empty()
+            || !chars
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b"-._~+/".contains(&b))
+            || !padding.bytes().all(|b| b == b'=')
+        {
+            return Err(ExtractTokenError::MalformedToken);
+        }
+
+        if token.is_some() {
+            return Err(ExtractTokenError::MultipleTokens);
+        }
+        token = Some(AccessToken::new(credentials.to_string()));
+    }
+    Ok(token)
+}
+
+fn query_token<B>(request: &http::Request<B>) -> Result<Option<AccessToken>, ExtractTokenError> {
+    single_param(request.uri().query().unwrap_or("").as_bytes())
+}
+
+fn single_param(input: &[u8]) -> Result<Option<AccessToken>, ExtractTokenError> {
+    let mut tokens = form_urlencoded::parse(input)
+        .filter(|(name, _)| name == ACCESS_TOKEN_PARAM)
+        .map(|(_, value)| value);
+    match (tokens.next(), tokens.next()) {
+        (Some(_), Some(_)) => Err(ExtractTokenError::MultipleTokens),
+        (Some(token), None) => Ok(Some(AccessToken::new(token.into_owned()))),
+        (None, _) => Ok(None),
+    }
+}
+
+fn is_form_request<B>(request: &http::Request<B>) -> bool {
+    // See https://tools.ietf.org/html/rfc6750#section-2.2.
+    request.method() != Method::GET
+        && request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| {
+                value
+                    .split(';')
+                    .next()
+                    .unwrap_or("")
+                    .trim()
+                    .eq_ignore_ascii_case("application/x-www-form-urlencoded")
+            })
+            .unwrap_or(false)
+}
+
+///
+/// Returns the HTTP status code that a protected resource should respond with when rejecting a
+/// request with `challenge`
+/// ([Section 3.1 of RFC 6750](https://tools.ietf.org/html/rfc6750#section-3.1)).
+///
+/// `invalid_request` errors map to `400 Bad Request`, `insufficient_scope` errors map to
+/// `403 Forbidden`, and all other challenges (including those without an error code) map to
+/// `401 Unauthorized`.
+///
+pub fn challenge_status(challenge: &Challenge) -> StatusCode {
+    match challenge.error() {
+        Some(BearerErrorResponseType::InvalidRequest) => StatusCode::BAD_REQUEST,
+        Some(BearerErrorResponseType::InsufficientScope) => StatusCode::FORBIDDEN,
+        _ => StatusCode::UNAUTHORIZED,
+    }
+}
+
+///
+/// Builds an empty error response carrying `challenge` in its `WWW-Authenticate` header, with
+/// the status code returned by [`challenge_status`].
+///
+/// Fails if any challenge parameter contains characters that are not allowed in HTTP headers.
+///
+pub fn challenge_response<B>(
+    challenge: &Challenge,
+) -> Result<http::Response<B>, http::header::InvalidHeaderValue>
+where
+    B: Default,
+{
+    let mut response = http::Response::new(B::default());
+    *response.status_mut() = challenge_status(challenge);
+    response
+        .headers_mut()
+        .insert(WWW_AUTHENTICATE, challenge.to_header_value()?);
+    response
+        .headers_mut()
+        .insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    Ok(response)
+}
+
+///
+/// Checks that a token introspection response (see [`Client::introspect`]) authorizes access
+/// to a protected resource requiring `required_scopes`.
+///
+/// Returns a `Bearer` challenge with an `invalid_token` error if the token is inactive, has
+/// expired or is not valid yet according to `now`, or an `insufficient_scope` error listing
+/// `required_scopes` if the token was not granted all of them.
+///
+/// [`Client::introspect`]: crate::Client::introspect
+///
+pub fn validate_introspection<TIR, TT>(
+    introspection: &TIR,
+    required_scopes: &[Scope],
+    now: DateTime<Utc>,
+) -> Result<(), Challenge>
+where
+    TIR: TokenIntrospectionResponse<TT>,
+    TT: TokenType,
+{
+    ClaimRequirements {
+        required_scopes: required_scopes.to_vec(),
+        clock_skew: Duration::from_secs(0),
+        ..ClaimRequirements::default()
+    }
+    .check(introspection, now)
+    .map_err(|rejection| rejection.to_challenge())
+}
+
+///
+/// Reason for which a [`TokenValidator`] rejected an access token.
+///
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum TokenRejection {
+    /// The authorization server reported the token as inactive (e.g., revoked or unknown).
+    #[error("Access token is inactive")]
+    Inactive,
+    /// The token's `exp` time has passed.
+    #[error("Access token has expired")]
+    Expired,
+    /// The token's `nbf` time has not been reached yet.
+    #[error("Access token is not valid yet")]
+    NotYetValid,
+    /// The token was not issued by the required issuer.
+    #[error("Access token was not issued by the expected issuer")]
+    InvalidIssuer,
+    /// The token is not intended for any of the required audiences.
+    #[error("Access token is not intended for this resource")]
+    InvalidAudience,
+    /// The token was not granted all of the required scopes, which are included in this
+    /// variant.
+    #[error("Access token was not granted the required scopes")]
+    InsufficientScope(Vec<Scope>),
+}
+impl TokenRejection {
+    ///
+    /// Returns the `Bearer` challenge that a protected resource should respond with
+    /// ([Section 3.1 of RFC 6750](https://tools.ietf.org/html/rfc6750#section-3.1)).
+    ///
+    pub fn to_challenge(&self) -> Challenge {
+        match self {
+            TokenRejection::InsufficientScope(required_scopes) => Challenge::bearer()
+                .set_error(BearerErrorResponseType::InsufficientScope)
+                .set_scopes(required_scopes),
+            other => Challenge::bearer()
+                .set_error(BearerErrorResponseType::InvalidToken)
+                .set_error_description(&other.to_string()),
+        }
+    }
+}
+
+///
+/// Error encountered while validating an access token with a [`TokenValidator`].
+///
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum TokenValidationError<RE, TE>
+where
+    RE: Error + 'static,
+    TE: ErrorResponse + 'static,
+{
+    ///
+    /// The token is not valid for accessing the protected resource.
+    ///
+    #[error("Access token rejected")]
+    Rejected(#[source] TokenRejection),
+    ///
+    /// The client has no introspection endpoint configured.
+    ///
+    #[error("Invalid introspection configuration")]
+    Configuration(#[source] ConfigurationError),
+    ///
+    /// The introspection request failed. The token's validity is unknown, so resource servers
+    /// should typically respond with a server error rather than a challenge.
+    ///
+    #[error("Introspection request failed")]
+    Request(#[source] RequestTokenError<RE, TE>),
+}
+
+///
+/// Claims required of an access token by a [`TokenValidator`] or a JWT validator.
+///
+#[derive(Clone, Debug)]
+pub(crate) struct ClaimRequirements {
+    pub(crate) required_scopes: Vec<Scope>,
+    pub(crate) audiences: Vec<String>,
+    pub(crate) issuer: Option<String>,
+    pub(crate) clock_skew: Duration,
+}
+impl Default for ClaimRequirements {
+    fn default() -> Self {
+        ClaimRequirements {
+            required_scopes: Vec::new(),
+            audiences: Vec::new(),
+            issuer: None,
+            clock_skew: Duration::from_secs(60),
+        }
+    }
+}
+impl ClaimRequirements {
+    pub(crate) fn check<TIR, TT>(
+        &self,
+        introspection: &TIR,
+        now: DateTime<Utc>,
+    ) -> Result<(), TokenRejection>
+    where
+        TIR: TokenIntrospectionResponse<TT>,
+        TT: TokenType,
+    {
+        if !introspection.active() {
+            return Err(TokenRejection::Inactive);
+        }
+
+        let clock_skew = chrono::Duration::from_std(self.clock_skew)
+            .unwrap_or_else(|_| chrono::Duration::zero());
+        // Timestamps too close to the limits of `DateTime` to adjust for clock skew are far
+        // enough in the future (for `exp`) or past (for `nbf`) to be considered valid.
+        match introspection
+            .exp()
+            .and_then(|exp| exp.checked_add_signed(clock_skew))
+        {
+            Some(exp) if exp <= now => return Err(TokenRejection::Expired),
+            _ => {}
+        }
+        match introspection
+            .nbf()
+            .and_then(|nbf| nbf.checked_sub_signed(clock_skew))
+        {
+            Some(nbf) if nbf > now => return Err(TokenRejection::NotYetValid),
+            _ => {}
+        }
+
+        if let Some(issuer) = self.issuer.as_ref() {
+            if introspection.iss() != Some(issuer.as_str()) {
+                return Err(TokenRejection::InvalidIssuer);
+            }
+        }
+
+        if !self.audiences.is_empty() {
+            let intended = match introspection.aud() {
+                Some(aud) => aud.iter().any(|aud| self.audiences.contains(aud)),
+                None => false,
+            };
+            if !intended {
+                return Err(TokenRejection::InvalidAudience);
+            }
+        }
+
+        let granted = introspection.scopes();
+        let has_required_scopes = self.required_scopes.iter().all(|scope| match granted {
+            Some(granted) => granted.contains(scope),
+            None => false,
+        });
+        if !has_required_scopes {
+            return Err(TokenRejection::InsufficientScope(
+                self.required_scopes.clone(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+struct CachedValidation<TIR> {
+    result: Result<TIR, TokenRejection>,
+    expires_at: Instant,
+}
+
+///
+/// Validates access tokens presented to a protected resource using
+/// [token introspection](https://tools.ietf.org/html/rfc7662) (see [`Client::introspect`]).
+///
+/// A token is accepted if the authorization server reports it as active, its `exp` and `nbf`
+/// times (if any) are satisfied allowing for the configured clock skew, and it was issued by the
+/// required issuer, for one of the required audiences and with all of the required scopes (if
+/// configured).
+///
+/// Validation results are cached by a SHA-256 hash of the token so that the raw tokens are not
+/// retained. Accepted tokens are cached for the configured TTL or until they expire, whichever
+/// comes first, and rejected tokens are cached for a shorter negative TTL. Failed introspection
+/// requests are not cached.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use oauth2::basic::BasicClient;
+/// # async fn err_wrapper(client: BasicClient, token: oauth2::AccessToken) -> Result<(), anyhow::Error> {
+/// use oauth2::reqwest::async_http_client;
+/// use oauth2::resource::{TokenValidationError, TokenValidator};
+/// use oauth2::Scope;
+///
+/// let validator = TokenValidator::new(client)
+///     .set_issuer("https://auth.example.com")
+///     .add_audience("https://api.example.com")
+///     .add_required_scope(Scope::new("read".to_string()));
+///
+/// match validator.validate_async(&token, async_http_client).await {
+///     Ok(introspection) => { /* Handle the request. */ }
+///     Err(TokenValidationError::Rejected(rejection)) => {
+///         let challenge = rejection.to_challenge();
+///         // Respond with `oauth2::resource::challenge_response(&challenge)`.
+///     }
+///     Err(err) => return Err(err.into()),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+pub struct TokenValidator<TE, TR, TT, TIR, RT, TRE>
+where
+    TE: ErrorResponse,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT>,
+    RT: RevocableToken,
+    TRE: ErrorResponse,
+{
+    client: Client<TE, TR, TT, TIR, RT, TRE>,
+    requirements: ClaimRequirements,
+    cache_ttl: Duration,
+    negative_cache_ttl: Duration,
+    max_cache_entries: usize,
+    cache: Mutex<HashMap<Vec<u8>, CachedValidation<TIR>>>,
+    time_fn: Arc<dyn Fn() -> DateTime<Utc> + Send + Sync>,
+}
+impl<TE, TR, TT, TIR, RT, TRE> TokenValidator<TE, TR, TT, TIR, RT, TRE>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT> + Clone,
+    RT: RevocableToken,
+    TRE: ErrorResponse + 'static,
+{
+    ///
+    /// Creates a validator that introspects tokens using the introspection endpoint of
+    /// `client`.
+    ///
+    /// By default, no scopes, audiences or issuer are required, the allowed clock skew is 60
+    /// seconds, accepted tokens are cached for up to 5 minutes, rejected tokens are cached for
+    /// 10 seconds, and at most 10,000 results are cached.
+    ///
+    pub fn new(client: Client<TE, TR, TT, TIR, RT, TRE>) -> Self {
+        TokenValidator {
+            client,
+            requirements: ClaimRequirements::default(),
+            cache_ttl: Duration::from_secs(300),
+            negative_cache_ttl: Duration::from_secs(10),
+            max_cache_entries: 10_000,
+            cache: Mutex::new(HashMap::new()),
+            time_fn: Arc::new(Utc::now),
+        }
+    }
+
+    ///
+    /// Requires tokens to have been granted `scope`.
+    ///
+    pub fn add_required_scope(mut self, scope: Scope) -> Self {
+        self.requirements.required_scopes.push(scope);
+        self
+    }
+
+    ///
+    /// Requires tokens to have been granted all of `scopes`.
+    ///
+    pub fn add_required_scopes<I>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator<Item = Scope>,
+    {
+        self.requirements.required_scopes.extend(scopes);
+        self
+    }
+
+    ///
+    /// Adds an audience that tokens may be intended for. If any audiences are added, tokens
+    /// must include at least one of them in their `aud` claim.
+    ///
+    pub fn add_audience(mut self, audience: &str) -> Self {
+        self.requirements.audiences.push(audience.to_string());
+        self
+    }
+
+    ///
+    /// Requires tokens to have been issued by `issuer` (their `iss` claim).
+    ///
+    pub fn set_issuer(mut self, issuer: &str) -> Self {
+        self.requirements.issuer = Some(issuer.to_string());
+        self
+    }
+
+    ///
+    /// Sets the allowed clock skew between this server and the authorization server when
+    /// checking `exp` and `nbf` times.
+    ///
+    pub fn set_clock_skew(mut self, clock_skew: Duration) -> Self {
+        self.requirements.clock_skew = clock_skew;
+        self
+    }
+
+    ///
+    /// Sets the maximum time for which accepted tokens are cached.
+    ///
+    pub fn set_cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = cache_ttl;
+        self
+    }
+
+    ///
+    /// Sets the time for which rejected tokens are cached.
+    ///
+    pub fn set_negative_cache_ttl(mut self, negative_cache_ttl: Duration) -> Self {
+        self.negative_cache_ttl = negative_cache_ttl;
+        self
+    }
+
+    ///
+    /// Sets the maximum number of cached validation results. Once reached, new results are not
+    /// cached until existing ones expire.
+    ///
+    pub fn set_max_cache_entries(mut self, max_cache_entries: usize) -> Self {
+        self.max_cache_entries = max_cache_entries;
+        self
+    }
+
+    ///
+    /// Specifies a function for returning the current time when checking `exp` and `nbf` times.
+    ///
+    pub fn set_time_fn<T>(mut self, time_fn: T) -> Self
+    where
+        T: Fn() -> DateTime<Utc> + Send + Sync + 'static,
+    {
+        self.time_fn = Arc::new(time_fn);
+        self
+    }
+
+    ///
+    /// Synchronously validates `token`, introspecting it with `http_client` unless a cached
+    /// result is available.
+    ///
+    pub fn validate<F, RE>(
+        &self,
+        token: &AccessToken,
+        http_client: F,
+    ) -> Result<TIR, TokenValidationError<RE, TE>>
+    where
+        F: FnOnce(HttpRequest) -> Result<HttpResponse, RE>,
+        RE: Error + 'static,
+    {
+        let key = cache_key(token);
+        if let Some(result) = self.cached(&key) {
+            return result.map_err(TokenValidationError::Rejected);
+        }
+
+        let introspection = self
+            .client
+            .introspect(token)
+            .map_err(TokenValidationError::Configuration)?
+            .request(http_client)
+            .map_err(TokenValidationError::Request)?;
+        self.check_and_cache(key, introspection)
+            .map_err(TokenValidationError::Rejected)
+    }
+
+    ///
+    /// Asynchronously validates `token`, introspecting it with `http_client` unless a cached
+    /// result is available.
+    ///
+    pub async fn validate_async<C, F, RE>(
+        &self,
+        token: &AccessToken,
+        http_client: C,
+    ) -> Result<TIR, TokenValidationError<RE, TE>>
+    where
+        C: FnOnce(HttpRequest) -> F,
+        F: Future<Output = Result<HttpResponse, RE>>,
+        RE: Error + 'static,
+    {
+        let key = cache_key(token);
+        if let Some(result) = self.cached(&key) {
+            return result.map_err(TokenValidationError::Rejected);
+        }
+
+        let introspection = self
+            .client
+            .introspect(token)
+            .map_err(TokenValidationError::Configuration)?
+            .request_async(http_client)
+            .await
+            .map_err(TokenValidationError::Request)?;
+        self.check_and_cache(key, introspection)
+            .map_err(TokenValidationError::Rejected)
+    }
+
+    fn cached(&self, key: &[u8]) -> Option<Result<TIR, TokenRejection>> {
+        let mut cache = self.cache.lock().unwrap();
+        let expired = match cache.get(key) {
+            Some(cached) if Instant::now() < cached.expires_at => {
+                return Some(cached.result.clone())
+            }
+            Some(_) => true,
+            None => false,
+        };
+        if expired {
+            cache.remove(key);
+        }
+        None
+    }
+
+    fn check_and_cache(&self, key: Vec<u8>, introspection: TIR) -> Result<TIR, TokenRejection> {
+        let now = (*self.time_fn)();
+        let result = self
+            .requirements
+            .check(&introspection, now)
+            .map(|()| introspection);
+
+        let ttl = match (&result, result.as_ref().ok().and_then(|tir| tir.exp())) {
+            (Ok(_), Some(exp)) => {
+                let clock_skew = chrono::Duration::from_std(self.requirements.clock_skew)
+                    .unwrap_or_else(|_| chrono::Duration::zero());
+                match exp.checked_add_signed(clock_skew) {
+                    Some(expires_at) => std::cmp::min(
+                        self.cache_ttl,
+                        expires_at
+                            .signed_duration_since(now)
+                            .to_std()
+                            .unwrap_or_default(),
+                    ),
+                    None => self.cache_ttl,
+                }
+            }
+            (Ok(_), None) => self.cache_ttl,
+            (Err(_), _) => self.negative_cache_ttl,
+        };
+
+        let now = Instant::now();
+        // A TTL too long to represent as an `Instant` is not cached.
+        if let Some(expires_at) = now
+            .checked_add(ttl)
+            .filter(|_| ttl > Duration::from_secs(0))
+        {
+            let mut cache = self.cache.lock().unwrap();
+            if cache.len() >= self.max_cache_entries {
+                cache.retain(|_, cached| now < cached.expires_at);
+            }
+            if cache.len() < self.max_cache_entries {
+                cache.insert(
+                    key,
+                    CachedValidation {
+                        result: result.clone(),
+                        expires_at,
+                    },
+                );
+            }
+        }
+        result
+    }
+}
+
+fn cache_key(token: &AccessToken) -> Vec<u8> {
+    Sha256::digest(token.secret().as_bytes()).to_vec()
+}
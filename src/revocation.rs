@@ -1,9 +1,13 @@
 use serde::{Deserialize, Serialize};
+use std::error::Error;
 use std::fmt::Error as FormatterError;
 use std::fmt::{Debug, Display, Formatter};
 
-use crate::{basic::BasicErrorResponseType, ErrorResponseType};
-use crate::{AccessToken, RefreshToken};
+use crate::{
+    basic::BasicErrorResponseType, ErrorResponse, ErrorResponseType, RequestTokenError,
+    StandardErrorResponse,
+};
+use crate::{AccessToken, IntrospectableToken, RefreshToken};
 
 ///
 /// A revocable token.
@@ -38,7 +42,8 @@ pub trait RevocableToken {
 ///
 /// Per [RFC 7009, Section 2](https://tools.ietf.org/html/rfc7009#section-2) prefer revocation by refresh token which,
 /// if issued to the client, must be supported by the server, otherwise fallback to access token (which may or may not
-/// be supported by the server).
+/// be supported by the server). [`revoke_token_response()`] implements this fallback for a token
+/// response.
 ///
 /// ```ignore
 /// let token_to_revoke: StandardRevocableToken = match token_response.refresh_token() {
@@ -53,6 +58,7 @@ pub trait RevocableToken {
 /// ```
 ///
 /// [`revoke_token()`]: crate::Client::revoke_token()
+/// [`revoke_token_response()`]: crate::Client::revoke_token_response()
 ///
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[non_exhaustive]
@@ -87,6 +93,16 @@ impl RevocableToken for StandardRevocableToken {
     }
 }
 
+impl IntrospectableToken for StandardRevocableToken {
+    fn secret(&self) -> &str {
+        RevocableToken::secret(self)
+    }
+
+    fn type_hint(&self) -> Option<&str> {
+        RevocableToken::type_hint(self)
+    }
+}
+
 impl From<AccessToken> for StandardRevocableToken {
     fn from(token: AccessToken) -> Self {
         Self::AccessToken(token)
@@ -111,6 +127,113 @@ impl From<&RefreshToken> for StandardRevocableToken {
     }
 }
 
+///
+/// Outcome of revoking one of the tokens in a token response using
+/// [`revoke_token_response()`](crate::Client::revoke_token_response()).
+///
+#[derive(Debug)]
+pub enum RevocationOutcome<RE, TE>
+where
+    RE: Error + 'static,
+    TE: ErrorResponse + 'static,
+{
+    ///
+    /// The server either revoked the token or the token was not known to the server.
+    ///
+    Revoked,
+    ///
+    /// Revocation of the token was not attempted, either because the token response does not
+    /// include it or because revoking it was unnecessary.
+    ///
+    NotAttempted,
+    ///
+    /// Revocation of the token failed.
+    ///
+    Failed(RequestTokenError<RE, TE>),
+}
+impl<RE, TE> RevocationOutcome<RE, TE>
+where
+    RE: Error + 'static,
+    TE: ErrorResponse + 'static,
+{
+    ///
+    /// Returns `true` if the token was revoked.
+    ///
+    pub fn is_revoked(&self) -> bool {
+        matches!(self, RevocationOutcome::Revoked)
+    }
+}
+
+///
+/// Per-token outcomes of revoking the tokens in a token response using
+/// [`revoke_token_response()`](crate::Client::revoke_token_response()).
+///
+#[derive(Debug)]
+pub struct TokenResponseRevocation<RE, TE>
+where
+    RE: Error + 'static,
+    TE: ErrorResponse + 'static,
+{
+    pub(crate) refresh_token: RevocationOutcome<RE, TE>,
+    pub(crate) access_token: RevocationOutcome<RE, TE>,
+}
+impl<RE, TE> TokenResponseRevocation<RE, TE>
+where
+    RE: Error + 'static,
+    TE: ErrorResponse + 'static,
+{
+    ///
+    /// Outcome of revoking the refresh token.
+    ///
+    pub fn refresh_token(&self) -> &RevocationOutcome<RE, TE> {
+        &self.refresh_token
+    }
+
+    ///
+    /// Outcome of revoking the access token.
+    ///
+    pub fn access_token(&self) -> &RevocationOutcome<RE, TE> {
+        &self.access_token
+    }
+
+    ///
+    /// Returns `true` if either token was revoked. Per
+    /// [Section 2.1 of RFC 7009](https://tools.ietf.org/html/rfc7009#section-2.1), revoking a
+    /// refresh token should also invalidate the access tokens issued with it.
+    ///
+    pub fn is_revoked(&self) -> bool {
+        self.refresh_token.is_revoked() || self.access_token.is_revoked()
+    }
+
+    ///
+    /// Returns the outcomes of revoking the refresh token and the access token, in that order.
+    ///
+    pub fn into_outcomes(self) -> (RevocationOutcome<RE, TE>, RevocationOutcome<RE, TE>) {
+        (self.refresh_token, self.access_token)
+    }
+}
+
+///
+/// Error response returned by a token revocation endpoint.
+///
+/// [`revoke_token_response()`](crate::Client::revoke_token_response()) uses this trait to detect
+/// servers that do not support revoking refresh tokens. Implement it for custom revocation error
+/// response types to use them with `revoke_token_response()`.
+///
+pub trait RevocationErrorResponse: ErrorResponse {
+    ///
+    /// Returns `true` if the server does not support the revocation of the presented token type
+    /// (i.e., responded with an `unsupported_token_type` error as defined in
+    /// [Section 2.2.1 of RFC 7009](https://tools.ietf.org/html/rfc7009#section-2.2.1)).
+    ///
+    fn is_unsupported_token_type(&self) -> bool;
+}
+impl RevocationErrorResponse for StandardErrorResponse<RevocationErrorResponseType> {
+    fn is_unsupported_token_type(&self) -> bool {
+        *self.error() == RevocationErrorResponseType::UnsupportedTokenType
+    }
+}
+
 ///
 /// OAuth 2.0 Token Revocation error response types.
 ///
@@ -0,0 +1,220 @@
+use super::devicecode::{DeviceAuthorizationResponse, ExtraDeviceAuthorizationFields};
+use super::{
+    AccessToken, AuthorizationCode, AuthorizationRequest, Client, ClientCredentialsTokenRequest,
+    CodeTokenRequest, ConfigurationError, CsrfToken, DeviceAccessTokenRequest,
+    DeviceAuthorizationRequest, ErrorResponse, IntrospectableToken, IntrospectionRequest,
+    RefreshToken, RefreshTokenRequest, RevocableToken, RevocationErrorResponse, RevocationRequest,
+    TokenIntrospectionResponse, TokenResponse, TokenResponseRevocationRequest, TokenType,
+};
+
+///
+/// Mode of an [`AuthorizationRequest`] created by a [`Client`], which may be turned into an
+/// authorization URL without validation using [`AuthorizationRequest::url`].
+///
+#[derive(Clone, Copy, Debug)]
+pub struct NonStrict;
+
+///
+/// Mode of an [`AuthorizationRequest`] created by a [`StrictClient`], which can only be turned
+/// into an authorization URL using
+/// [`try_url`](AuthorizationRequest::<Strict>::try_url) so that strict mode is enforced.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct Strict;
+
+///
+/// Variant of [`Client`] that enforces the requirements of
+/// [OAuth 2.1](https://tools.ietf.org/html/draft-ietf-oauth-v2-1) and the
+/// [OAuth 2.0 Security Best Current Practice](https://tools.ietf.org/html/draft-ietf-oauth-security-topics),
+/// returned by [`Client::set_strict_mode`].
+///
+/// A strict client differs from [`Client`] as follows:
+///  * The [Implicit Grant](https://tools.ietf.org/html/rfc6749#section-4.2) and the
+///    [Resource Owner Password Credentials Grant](https://tools.ietf.org/html/rfc6749#section-4.3)
+///    are not supported, so there is no `use_implicit_flow` or `exchange_password` method. A
+///    `token` response type set using [`AuthorizationRequest::set_response_type`] is rejected
+///    with [`ConfigurationError::UnsupportedFlow`].
+///  * Authorization requests can only be turned into an authorization URL using
+///    [`try_url`](AuthorizationRequest::<Strict>::try_url), which requires a PKCE code challenge
+///    using the `S256` method (see [`AuthorizationRequest::set_pkce_challenge`]).
+///  * Authorization code token requests must include both the PKCE verifier (see
+///    [`CodeTokenRequest::set_pkce_verifier`]) and the challenge it must match (see
+///    [`CodeTokenRequest::set_expected_pkce_challenge`]), or they fail with
+///    [`ConfigurationError::PkceVerifierRequired`] or
+///    [`ConfigurationError::PkceChallengeRequired`].
+///  * Endpoint and redirect URLs must use HTTPS, except for plain HTTP to a loopback address.
+///
+/// Violations detected while sending a token request are returned as
+/// [`RequestTokenError::Other`](crate::RequestTokenError::Other) without sending the request.
+///
+/// # Example
+///
+/// ```rust
+/// use oauth2::basic::BasicClient;
+/// use oauth2::{AuthUrl, ClientId, CsrfToken, PkceCodeChallenge, TokenUrl};
+///
+/// # fn err_wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = BasicClient::new(
+///     ClientId::new("client_id".to_string()),
+///     None,
+///     AuthUrl::new("https://example.com/auth".to_string())?,
+///     Some(TokenUrl::new("https://example.com/token".to_string())?),
+/// )
+/// .set_strict_mode();
+///
+/// let (pkce_challenge, _pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+/// let (auth_url, _csrf_token) = client
+///     .authorize_url(CsrfToken::new_random)
+///     .set_pkce_challenge(pkce_challenge)
+///     .try_url()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Building an authorization URL without enforcing strict mode fails to compile:
+///
+/// ```rust,compile_fail
+/// use oauth2::basic::BasicClient;
+/// use oauth2::{AuthUrl, ClientId, CsrfToken};
+///
+/// let client = BasicClient::new(
+///     ClientId::new("client_id".to_string()),
+///     None,
+///     AuthUrl::new("https://example.com/auth".to_string()).unwrap(),
+///     None,
+/// )
+/// .set_strict_mode();
+///
+/// let (auth_url, _csrf_token) = client.authorize_url(CsrfToken::new_random).url();
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct StrictClient<TE, TR, TT, TIR, RT, TRE>
+where
+    TE: ErrorResponse,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT>,
+    RT: RevocableToken,
+    TRE: ErrorResponse,
+{
+    inner: Client<TE, TR, TT, TIR, RT, TRE>,
+}
+
+impl<TE, TR, TT, TIR, RT, TRE> StrictClient<TE, TR, TT, TIR, RT, TRE>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT>,
+    RT: RevocableToken,
+    TRE: ErrorResponse + 'static,
+{
+    pub(crate) fn new(inner: Client<TE, TR, TT, TIR, RT, TRE>) -> Self {
+        StrictClient { inner }
+    }
+
+    ///
+    /// Generates an authorization URL for a new authorization request (see
+    /// [`Client::authorize_url`]), which must be built using
+    /// [`try_url`](AuthorizationRequest::<Strict>::try_url).
+    ///
+    pub fn authorize_url<S>(&self, state_fn: S) -> AuthorizationRequest<'_, Strict>
+    where
+        S: FnOnce() -> CsrfToken,
+    {
+        self.inner.authorization_request(state_fn)
+    }
+
+    ///
+    /// Exchanges a code produced by a successful authorization process with an access token
+    /// (see [`Client::exchange_code`]).
+    ///
+    pub fn exchange_code(&self, code: AuthorizationCode) -> CodeTokenRequest<'_, TE, TR, TT> {
+        self.inner.exchange_code(code)
+    }
+
+    ///
+    /// Requests an access token for the *client credentials* grant type (see
+    /// [`Client::exchange_client_credentials`]).
+    ///
+    pub fn exchange_client_credentials(&self) -> ClientCredentialsTokenRequest<'_, TE, TR, TT> {
+        self.inner.exchange_client_credentials()
+    }
+
+    ///
+    /// Exchanges a refresh token for an access token (see
+    /// [`Client::exchange_refresh_token`]).
+    ///
+    pub fn exchange_refresh_token<'a, 'b>(
+        &'a self,
+        refresh_token: &'b RefreshToken,
+    ) -> RefreshTokenRequest<'b, TE, TR, TT>
+    where
+        'a: 'b,
+    {
+        self.inner.exchange_refresh_token(refresh_token)
+    }
+
+    ///
+    /// Performs a device authorization request (see [`Client::exchange_device_code`]).
+    ///
+    pub fn exchange_device_code(
+        &self,
+    ) -> Result<DeviceAuthorizationRequest<'_, TE>, ConfigurationError> {
+        self.inner.exchange_device_code()
+    }
+
+    ///
+    /// Performs a device access token request (see [`Client::exchange_device_access_token`]).
+    ///
+    pub fn exchange_device_access_token<'a, 'b, 'c, EF>(
+        &'a self,
+        auth_response: &'b DeviceAuthorizationResponse<EF>,
+    ) -> DeviceAccessTokenRequest<'b, 'c, TR, TT, EF>
+    where
+        'a: 'b,
+        EF: ExtraDeviceAuthorizationFields,
+    {
+        self.inner.exchange_device_access_token(auth_response)
+    }
+
+    ///
+    /// Queries the introspection endpoint to determine the set of metadata for a previously
+    /// received token (see [`Client::introspect`]).
+    ///
+    pub fn introspect<'a, T>(
+        &'a self,
+        token: &'a T,
+    ) -> Result<IntrospectionRequest<'a, TE, TIR, TT>, ConfigurationError>
+    where
+        T: IntrospectableToken,
+    {
+        self.inner.introspect(token)
+    }
+
+    ///
+    /// Attempts to revoke the given previously received token (see [`Client::revoke_token`]).
+    ///
+    pub fn revoke_token(
+        &self,
+        token: RT,
+    ) -> Result<RevocationRequest<'_, RT, TRE>, ConfigurationError> {
+        self.inner.revoke_token(token)
+    }
+
+    ///
+    /// Attempts to revoke the tokens in `token_response` (see
+    /// [`Client::revoke_token_response`]).
+    ///
+    pub fn revoke_token_response(
+        &self,
+        token_response: &TR,
+    ) -> Result<TokenResponseRevocationRequest<'_, RT, TRE>, ConfigurationError>
+    where
+        RT: From<AccessToken> + From<RefreshToken>,
+        TRE: RevocationErrorResponse,
+    {
+        self.inner.revoke_token_response(token_response)
+    }
+}
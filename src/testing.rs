@@ -0,0 +1,491 @@
+use std::collections::HashSet;
+use std::fs;
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use futures_util::future::{ready, BoxFuture, Ready};
+use http::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use http::method::Method;
+use http::status::StatusCode;
+use serde::{Deserialize, Serialize};
+use url::{form_urlencoded, Url};
+
+use crate::{HttpRequest, HttpResponse};
+
+// Replaces the values of credentials that should not be written to cassette files.
+const REDACTED: &str = "[redacted]";
+
+// Request parameters that contain client credentials and are redacted before recording.
+const REDACTED_PARAMS: &[&str] = &["client_secret", "client_assertion"];
+
+///
+/// Error type returned by cassette and replay operations.
+///
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Failed to read or write a cassette file.
+    #[error("IO error")]
+    IO(#[from] std::io::Error),
+    /// Failed to serialize or deserialize a cassette.
+    #[error("Failed to serialize or deserialize cassette")]
+    Json(#[from] serde_json::Error),
+    /// Cassette contains an invalid request or response.
+    #[error("Invalid cassette: {}", _0)]
+    InvalidCassette(String),
+    /// No unused interaction in the cassette matches the request.
+    #[error("No recorded interaction matches {} {}", _0, _1)]
+    NoMatchingInteraction(Method, Url),
+}
+
+///
+/// A recorded HTTP request.
+///
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RecordedRequest {
+    /// HTTP request method.
+    pub method: String,
+    /// Request URL.
+    pub url: String,
+    /// Request headers, in the order they were sent.
+    pub headers: Vec<(String, String)>,
+    /// Request body, decoded as UTF-8.
+    pub body: String,
+}
+
+///
+/// A recorded HTTP response.
+///
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RecordedResponse {
+    /// HTTP status code.
+    pub status_code: u16,
+    /// Response headers, in the order they were received.
+    pub headers: Vec<(String, String)>,
+    /// Response body, decoded as UTF-8.
+    pub body: String,
+}
+
+///
+/// A recorded request along with the response returned by the server.
+///
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Interaction {
+    /// The request sent to the server.
+    pub request: RecordedRequest,
+    /// The response returned by the server.
+    pub response: RecordedResponse,
+}
+
+///
+/// An ordered list of recorded HTTP interactions that can be saved to and loaded from a JSON
+/// file.
+///
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Cassette {
+    /// The recorded interactions, in the order they occurred.
+    pub interactions: Vec<Interaction>,
+}
+impl Cassette {
+    ///
+    /// Creates an empty cassette.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Loads a cassette from a JSON file.
+    ///
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    ///
+    /// Saves the cassette to a JSON file, replacing any existing file.
+    ///
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        Ok(fs::write(path, serde_json::to_vec_pretty(self)?)?)
+    }
+}
+
+///
+/// Records the HTTP interactions performed by a wrapped HTTP client.
+///
+/// The `Authorization` header and the `client_secret` and `client_assertion` request parameters
+/// are redacted before being recorded so that client credentials are not written to cassette
+/// files. Access and refresh tokens returned by the server are recorded as-is, since they're
+/// needed to replay the interaction.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use oauth2::basic::BasicClient;
+/// # fn err_wrapper(client: BasicClient) -> Result<(), anyhow::Error> {
+/// use oauth2::reqwest::http_client;
+/// use oauth2::testing::Recorder;
+///
+/// let recorder = Recorder::new();
+/// let token_result = client
+///     .exchange_client_credentials()
+///     .request(recorder.http_client(http_client))?;
+/// recorder.cassette().save("tests/cassettes/client_credentials.json")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+#[derive(Clone, Debug, Default)]
+pub struct Recorder {
+    interactions: Arc<Mutex<Vec<Interaction>>>,
+}
+impl Recorder {
+    ///
+    /// Creates a recorder with no recorded interactions.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Returns a synchronous HTTP client that sends requests through `http_client` and records
+    /// each successful interaction.
+    ///
+    pub fn http_client<C, RE>(
+        &self,
+        http_client: C,
+    ) -> impl Fn(HttpRequest) -> Result<HttpResponse, RE>
+    where
+        C: Fn(HttpRequest) -> Result<HttpResponse, RE>,
+    {
+        let interactions = self.interactions.clone();
+        move |request| {
+            let recorded_request = record_request(&request);
+            let response = http_client(request)?;
+            record(&interactions, recorded_request, &response);
+            Ok(response)
+        }
+    }
+
+    ///
+    /// Returns an asynchronous HTTP client that sends requests through `http_client` and records
+    /// each successful interaction.
+    ///
+    pub fn async_http_client<C, F, RE>(
+        &self,
+        http_client: C,
+    ) -> impl Fn(HttpRequest) -> BoxFuture<'static, Result<HttpResponse, RE>>
+    where
+        C: Fn(HttpRequest) -> F,
+        F: Future<Output = Result<HttpResponse, RE>> + Send + 'static,
+    {
+        let interactions = self.interactions.clone();
+        move |request| {
+            let recorded_request = record_request(&request);
+            let interactions = interactions.clone();
+            let response = http_client(request);
+            Box::pin(async move {
+                let response = response.await?;
+                record(&interactions, recorded_request, &response);
+                Ok(response)
+            })
+        }
+    }
+
+    ///
+    /// Returns a cassette containing the interactions recorded so far.
+    ///
+    pub fn cassette(&self) -> Cassette {
+        Cassette {
+            interactions: self.interactions.lock().unwrap().clone(),
+        }
+    }
+}
+
+fn record(
+    interactions: &Mutex<Vec<Interaction>>,
+    request: RecordedRequest,
+    response: &HttpResponse,
+) {
+    interactions.lock().unwrap().push(Interaction {
+        request,
+        response: RecordedResponse {
+            status_code: response.status_code.as_u16(),
+            headers: record_headers(&response.headers),
+            body: String::from_utf8_lossy(&response.body).into_owned(),
+        },
+    });
+}
+
+fn record_request(request: &HttpRequest) -> RecordedRequest {
+    let mut headers = record_headers(&request.headers);
+    for (name, value) in headers.iter_mut() {
+        if name.eq_ignore_ascii_case(AUTHORIZATION.as_str()) {
+            *value = REDACTED.to_string();
+        }
+    }
+
+    let body = if is_form(&request.headers) {
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(form_urlencoded::parse(&request.body).map(|(name, value)| {
+                if REDACTED_PARAMS.contains(&name.as_ref()) {
+                    (name, REDACTED.into())
+                } else {
+                    (name, value)
+                }
+            }))
+            .finish()
+    } else {
+        String::from_utf8_lossy(&request.body).into_owned()
+    };
+
+    RecordedRequest {
+        method: request.method.to_string(),
+        url: request.url.to_string(),
+        headers,
+        body,
+    }
+}
+
+fn record_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|value| (name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn is_form(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with("application/x-www-form-urlencoded"))
+        .unwrap_or(false)
+}
+
+///
+/// Rules that determine whether a request matches a recorded interaction.
+///
+/// A request matches if its method and URL path are equal to the recorded ones, and its query
+/// parameters and (for form-encoded requests) body parameters are equal to the recorded ones,
+/// regardless of order. Parameters with volatile values (by default, `state`, `nonce`,
+/// `code_verifier`, `code_challenge` and the redacted client credentials) are ignored. Request
+/// headers are only compared if added via [`MatchRules::match_header`].
+///
+#[derive(Clone, Debug)]
+pub struct MatchRules {
+    ignored_params: HashSet<String>,
+    matched_headers: Vec<HeaderName>,
+}
+impl MatchRules {
+    ///
+    /// Creates match rules that ignore the default set of volatile parameters.
+    ///
+    pub fn new() -> Self {
+        MatchRules {
+            ignored_params: [
+                "state",
+                "nonce",
+                "code_verifier",
+                "code_challenge",
+                "client_secret",
+                "client_assertion",
+            ]
+            .iter()
+            .map(|param| param.to_string())
+            .collect(),
+            matched_headers: Vec::new(),
+        }
+    }
+
+    ///
+    /// Ignores the query or form parameter `name` when matching requests.
+    ///
+    pub fn ignore_param(mut self, name: &str) -> Self {
+        self.ignored_params.insert(name.to_string());
+        self
+    }
+
+    ///
+    /// Requires the value of the request header `name` to equal the recorded one.
+    ///
+    pub fn match_header(mut self, name: HeaderName) -> Self {
+        self.matched_headers.push(name);
+        self
+    }
+
+    ///
+    /// Returns `true` if `request` matches `recorded`.
+    ///
+    pub fn matches(&self, request: &HttpRequest, recorded: &RecordedRequest) -> bool {
+        let recorded_url = match Url::parse(&recorded.url) {
+            Ok(url) => url,
+            Err(_) => return false,
+        };
+
+        if request.method.as_str() != recorded.method
+            || request.url.scheme() != recorded_url.scheme()
+            || request.url.host_str() != recorded_url.host_str()
+            || request.url.port_or_known_default() != recorded_url.port_or_known_default()
+            || request.url.path() != recorded_url.path()
+            || self.params(request.url.query().unwrap_or("").as_bytes())
+                != self.params(recorded_url.query().unwrap_or("").as_bytes())
+        {
+            return false;
+        }
+
+        for name in &self.matched_headers {
+            let value = request
+                .headers
+                .get(name)
+                .and_then(|value| value.to_str().ok());
+            let recorded_value = recorded
+                .headers
+                .iter()
+                .find(|(recorded_name, _)| recorded_name.eq_ignore_ascii_case(name.as_str()))
+                .map(|(_, value)| value.as_str());
+            if value != recorded_value {
+                return false;
+            }
+        }
+
+        if is_form(&request.headers) {
+            self.params(&request.body) == self.params(recorded.body.as_bytes())
+        } else {
+            request.body == recorded.body.as_bytes()
+        }
+    }
+
+    fn params(&self, input: &[u8]) -> Vec<(String, String)> {
+        let mut params = form_urlencoded::parse(input)
+            .filter(|(name, _)| !self.ignored_params.contains(name.as_ref()))
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect::<Vec<_>>();
+        params.sort();
+        params
+    }
+}
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+///
+/// Serves the responses recorded in a [`Cassette`] back to matching requests.
+///
+/// Each recorded interaction is replayed at most once, and requests are matched against the
+/// unused interactions in the order they were recorded. Requests that don't match any unused
+/// interaction fail with [`Error::NoMatchingInteraction`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use oauth2::basic::BasicClient;
+/// # fn err_wrapper(client: BasicClient) -> Result<(), anyhow::Error> {
+/// use oauth2::testing::{Cassette, Replayer};
+///
+/// let replayer = Replayer::new(Cassette::load("tests/cassettes/client_credentials.json")?);
+/// let token_result = client
+///     .exchange_client_credentials()
+///     .request(replayer.http_client())?;
+/// assert_eq!(0, replayer.remaining());
+/// # Ok(())
+/// # }
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct Replayer {
+    interactions: Arc<Mutex<Vec<Option<Interaction>>>>,
+    match_rules: Arc<MatchRules>,
+}
+impl Replayer {
+    ///
+    /// Creates a replayer that serves the interactions recorded in `cassette`.
+    ///
+    pub fn new(cassette: Cassette) -> Self {
+        Replayer {
+            interactions: Arc::new(Mutex::new(
+                cassette.interactions.into_iter().map(Some).collect(),
+            )),
+            match_rules: Arc::new(MatchRules::new()),
+        }
+    }
+
+    ///
+    /// Sets the rules used to match requests against recorded interactions.
+    ///
+    pub fn set_match_rules(mut self, match_rules: MatchRules) -> Self {
+        self.match_rules = Arc::new(match_rules);
+        self
+    }
+
+    ///
+    /// Returns a synchronous HTTP client that serves recorded responses.
+    ///
+    pub fn http_client(&self) -> impl Fn(HttpRequest) -> Result<HttpResponse, Error> {
+        let replayer = self.clone();
+        move |request| replayer.replay(&request)
+    }
+
+    ///
+    /// Returns an asynchronous HTTP client that serves recorded responses.
+    ///
+    pub fn async_http_client(&self) -> impl Fn(HttpRequest) -> Ready<Result<HttpResponse, Error>> {
+        let replayer = self.clone();
+        move |request| ready(replayer.replay(&request))
+    }
+
+    ///
+    /// Returns the number of recorded interactions that have not been replayed yet.
+    ///
+    pub fn remaining(&self) -> usize {
+        self.interactions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|interaction| interaction.is_some())
+            .count()
+    }
+
+    fn replay(&self, request: &HttpRequest) -> Result<HttpResponse, Error> {
+        let interaction = self
+            .interactions
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|interaction| match interaction {
+                Some(interaction) => self.match_rules.matches(request, &interaction.request),
+                None => false,
+            })
+            .and_then(Option::take)
+            .ok_or_else(|| {
+                Error::NoMatchingInteraction(request.method.clone(), request.url.clone())
+            })?;
+
+        let response = interaction.response;
+        let status_code = StatusCode::from_u16(response.status_code).map_err(|_| {
+            Error::InvalidCassette(format!("invalid status code {}", response.status_code))
+        })?;
+        let mut headers = HeaderMap::new();
+        for (name, value) in response.headers {
+            headers.append(
+                HeaderName::from_bytes(name.as_bytes()).map_err(|_| {
+                    Error::InvalidCassette(format!("invalid header name {:?}", name))
+                })?,
+                HeaderValue::from_str(&value).map_err(|_| {
+                    Error::InvalidCassette(format!("invalid {} header value {:?}", name, value))
+                })?,
+            );
+        }
+
+        Ok(HttpResponse {
+            status_code,
+            headers,
+            body: response.body.into_bytes(),
+        })
+    }
+}
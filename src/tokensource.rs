@@ -0,0 +1,390 @@
+use std::error::Error;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use futures_util::future::BoxFuture;
+use futures_util::lock::Mutex as AsyncMutex;
+#[cfg(any(feature = "tower", feature = "reqwest-middleware"))]
+use http::header::HeaderMap;
+
+#[cfg(any(feature = "tower", feature = "reqwest-middleware"))]
+use crate::bearer::{BearerErrorResponseType, Challenge};
+use crate::{
+    AccessToken, Client, ErrorResponse, HttpRequest, HttpResponse, RefreshToken, RequestTokenError,
+    RevocableToken, Scope, TokenIntrospectionResponse, TokenResponse, TokenType,
+};
+
+/// Tokens are treated as expired this long before their actual expiration time, so that they
+/// don't expire while a request is in flight.
+const DEFAULT_EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+///
+/// A source of access tokens for authorizing requests to protected resources.
+///
+/// Implementations are expected to cache tokens and only contact the authorization server once
+/// the cached token has expired or has been invalidated.
+///
+pub trait TokenSource: Send + Sync {
+    ///
+    /// Error returned when no access token could be obtained.
+    ///
+    type Error: Error + Send + Sync + 'static;
+
+    ///
+    /// Returns an unexpired access token, fetching a new one from the authorization server if
+    /// necessary.
+    ///
+    fn token(&self) -> BoxFuture<'_, Result<AccessToken, Self::Error>>;
+
+    ///
+    /// Discards the cached access token if it matches `token`, forcing the next call to
+    /// [`token`](Self::token) to fetch a new one.
+    ///
+    /// This should be called when a protected resource rejects `token` (e.g., with an
+    /// `invalid_token` error as defined in [RFC 6750](https://tools.ietf.org/html/rfc6750#section-3.1)).
+    /// Tokens other than the cached one are ignored, so concurrent callers invalidating the same
+    /// token trigger only a single refresh.
+    ///
+    fn invalidate(&self, token: &AccessToken);
+}
+
+struct CachedToken {
+    access_token: AccessToken,
+    expires_at: Option<Instant>,
+}
+
+struct TokenCache {
+    cached: Mutex<Option<CachedToken>>,
+    // Held while fetching a new token so that concurrent callers share a single request.
+    fetch_lock: AsyncMutex<()>,
+    expiry_margin: Duration,
+}
+impl TokenCache {
+    fn new() -> Self {
+        TokenCache {
+            cached: Mutex::new(None),
+            fetch_lock: AsyncMutex::new(()),
+            expiry_margin: DEFAULT_EXPIRY_MARGIN,
+        }
+    }
+
+    fn get(&self) -> Option<AccessToken> {
+        let cached = self.cached.lock().unwrap();
+        cached
+            .as_ref()
+            .filter(|cached| match cached.expires_at {
+                Some(expires_at) => Instant::now() < expires_at,
+                None => true,
+            })
+            .map(|cached| cached.access_token.clone())
+    }
+
+    fn set(&self, access_token: AccessToken, expires_in: Option<Duration>) {
+        let expires_at = expires_in.map(|expires_in| {
+            Instant::now()
+                + expires_in
+                    .checked_sub(self.expiry_margin)
+                    .unwrap_or_default()
+        });
+        *self.cached.lock().unwrap() = Some(CachedToken {
+            access_token,
+            expires_at,
+        });
+    }
+
+    fn invalidate(&self, token: &AccessToken) {
+        let mut cached = self.cached.lock().unwrap();
+        let matches = match *cached {
+            Some(ref cached) => cached.access_token.secret() == token.secret(),
+            None => false,
+        };
+        if matches {
+            *cached = None;
+        }
+    }
+
+    async fn get_or_fetch<FN, FU, E>(&self, fetch: FN) -> Result<AccessToken, E>
+    where
+        FN: FnOnce() -> FU,
+        FU: Future<Output = Result<(AccessToken, Option<Duration>), E>>,
+    {
+        if let Some(access_token) = self.get() {
+            return Ok(access_token);
+        }
+
+        let _guard = self.fetch_lock.lock().await;
+        // Another caller may have fetched a new token while we were waiting for the lock.
+        if let Some(access_token) = self.get() {
+            return Ok(access_token);
+        }
+
+        let (access_token, expires_in) = fetch().await?;
+        self.set(access_token.clone(), expires_in);
+        Ok(access_token)
+    }
+}
+
+///
+/// A [`TokenSource`] that obtains access tokens using the
+/// [Client Credentials Grant](https://tools.ietf.org/html/rfc6749#section-4.4).
+///
+/// See [`Client::exchange_client_credentials`].
+///
+pub struct ClientCredentialsTokenSource<TE, TR, TT, TIR, RT, TRE, C>
+where
+    TE: ErrorResponse,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT>,
+    RT: RevocableToken,
+    TRE: ErrorResponse,
+{
+    client: Client<TE, TR, TT, TIR, RT, TRE>,
+    http_client: C,
+    scopes: Vec<Scope>,
+    cache: TokenCache,
+}
+impl<TE, TR, TT, TIR, RT, TRE, C> ClientCredentialsTokenSource<TE, TR, TT, TIR, RT, TRE, C>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT>,
+    RT: RevocableToken,
+    TRE: ErrorResponse + 'static,
+{
+    ///
+    /// Creates a token source that requests tokens from the token endpoint of `client` using the
+    /// given asynchronous HTTP client.
+    ///
+    pub fn new(client: Client<TE, TR, TT, TIR, RT, TRE>, http_client: C) -> Self {
+        ClientCredentialsTokenSource {
+            client,
+            http_client,
+            scopes: Vec::new(),
+            cache: TokenCache::new(),
+        }
+    }
+
+    ///
+    /// Appends a new scope to each token request.
+    ///
+    pub fn add_scope(mut self, scope: Scope) -> Self {
+        self.scopes.push(scope);
+        self
+    }
+
+    ///
+    /// Appends a collection of scopes to each token request.
+    ///
+    pub fn add_scopes<I>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator<Item = Scope>,
+    {
+        self.scopes.extend(scopes);
+        self
+    }
+
+    ///
+    /// Sets how long before its expiration a cached token is considered expired.
+    ///
+    /// The default is 30 seconds.
+    ///
+    pub fn set_expiry_margin(mut self, expiry_margin: Duration) -> Self {
+        self.cache.expiry_margin = expiry_margin;
+        self
+    }
+}
+impl<TE, TR, TT, TIR, RT, TRE, C, F, RE> TokenSource
+    for ClientCredentialsTokenSource<TE, TR, TT, TIR, RT, TRE, C>
+where
+    TE: ErrorResponse + Send + Sync + 'static,
+    TR: TokenResponse<TT> + Send + Sync,
+    TT: TokenType + Send + Sync,
+    TIR: TokenIntrospectionResponse<TT> + Send + Sync,
+    RT: RevocableToken + Send + Sync,
+    TRE: ErrorResponse + Send + Sync + 'static,
+    C: Fn(HttpRequest) -> F + Send + Sync,
+    F: Future<Output = Result<HttpResponse, RE>> + Send,
+    RE: Error + Send + Sync + 'static,
+{
+    type Error = RequestTokenError<RE, TE>;
+
+    fn token(&self) -> BoxFuture<'_, Result<AccessToken, Self::Error>> {
+        Box::pin(self.cache.get_or_fetch(move || async move {
+            let token_response = self
+                .client
+                .exchange_client_credentials()
+                .add_scopes(self.scopes.iter().cloned())
+                .request_async(&self.http_client)
+                .await?;
+            Ok((
+                token_response.access_token().clone(),
+                token_response.expires_in(),
+            ))
+        }))
+    }
+
+    fn invalidate(&self, token: &AccessToken) {
+        self.cache.invalidate(token)
+    }
+}
+
+///
+/// A [`TokenSource`] that obtains access tokens by
+/// [refreshing](https://tools.ietf.org/html/rfc6749#section-6) a previously issued refresh
+/// token.
+///
+/// If the authorization server issues a new refresh token along with an access token, the new
+/// refresh token replaces the previous one for subsequent refreshes.
+///
+/// See [`Client::exchange_refresh_token`].
+///
+pub struct RefreshTokenSource<TE, TR, TT, TIR, RT, TRE, C>
+where
+    TE: ErrorResponse,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT>,
+    RT: RevocableToken,
+    TRE: ErrorResponse,
+{
+    client: Client<TE, TR, TT, TIR, RT, TRE>,
+    http_client: C,
+    refresh_token: Mutex<RefreshToken>,
+    scopes: Vec<Scope>,
+    cache: TokenCache,
+}
+impl<TE, TR, TT, TIR, RT, TRE, C> RefreshTokenSource<TE, TR, TT, TIR, RT, TRE, C>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT>,
+    RT: RevocableToken,
+    TRE: ErrorResponse + 'static,
+{
+    ///
+    /// Creates a token source that exchanges `refresh_token` for access tokens at the token
+    /// endpoint of `client` using the given asynchronous HTTP client.
+    ///
+    pub fn new(
+        client: Client<TE, TR, TT, TIR, RT, TRE>,
+        refresh_token: RefreshToken,
+        http_client: C,
+    ) -> Self {
+        RefreshTokenSource {
+            client,
+            http_client,
+            refresh_token: Mutex::new(refresh_token),
+            scopes: Vec::new(),
+            cache: TokenCache::new(),
+        }
+    }
+
+    ///
+    /// Creates a token source from a token response that includes a refresh token.
+    ///
+    /// The access token from `token_response` is used until it expires. Returns `None` if
+    /// `token_response` does not include a refresh token.
+    ///
+    pub fn from_token_response(
+        client: Client<TE, TR, TT, TIR, RT, TRE>,
+        token_response: &TR,
+        http_client: C,
+    ) -> Option<Self> {
+        let source = Self::new(client, token_response.refresh_token()?.clone(), http_client);
+        source.cache.set(
+            token_response.access_token().clone(),
+            token_response.expires_in(),
+        );
+        Some(source)
+    }
+
+    ///
+    /// Appends a new scope to each refresh request.
+    ///
+    pub fn add_scope(mut self, scope: Scope) -> Self {
+        self.scopes.push(scope);
+        self
+    }
+
+    ///
+    /// Appends a collection of scopes to each refresh request.
+    ///
+    pub fn add_scopes<I>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator<Item = Scope>,
+    {
+        self.scopes.extend(scopes);
+        self
+    }
+
+    ///
+    /// Sets how long before its expiration a cached token is considered expired.
+    ///
+    /// The default is 30 seconds.
+    ///
+    pub fn set_expiry_margin(mut self, expiry_margin: Duration) -> Self {
+        self.cache.expiry_margin = expiry_margin;
+        self
+    }
+
+    ///
+    /// Returns the refresh token that will be used for the next refresh.
+    ///
+    pub fn refresh_token(&self) -> RefreshToken {
+        self.refresh_token.lock().unwrap().clone()
+    }
+}
+impl<TE, TR, TT, TIR, RT, TRE, C, F, RE> TokenSource
+    for RefreshTokenSource<TE, TR, TT, TIR, RT, TRE, C>
+where
+    TE: ErrorResponse + Send + Sync + 'static,
+    TR: TokenResponse<TT> + Send + Sync,
+    TT: TokenType + Send + Sync,
+    TIR: TokenIntrospectionResponse<TT> + Send + Sync,
+    RT: RevocableToken + Send + Sync,
+    TRE: ErrorResponse + Send + Sync + 'static,
+    C: Fn(HttpRequest) -> F + Send + Sync,
+    F: Future<Output = Result<HttpResponse, RE>> + Send,
+    RE: Error + Send + Sync + 'static,
+{
+    type Error = RequestTokenError<RE, TE>;
+
+    fn token(&self) -> BoxFuture<'_, Result<AccessToken, Self::Error>> {
+        Box::pin(self.cache.get_or_fetch(move || async move {
+            let refresh_token = self.refresh_token();
+            let token_response = self
+                .client
+                .exchange_refresh_token(&refresh_token)
+                .add_scopes(self.scopes.iter().cloned())
+                .request_async(&self.http_client)
+                .await?;
+            if let Some(new_refresh_token) = token_response.refresh_token() {
+                *self.refresh_token.lock().unwrap() = new_refresh_token.clone();
+            }
+            Ok((
+                token_response.access_token().clone(),
+                token_response.expires_in(),
+            ))
+        }))
+    }
+
+    fn invalidate(&self, token: &AccessToken) {
+        self.cache.invalidate(token)
+    }
+}
+
+///
+/// Returns `true` if `headers` contain a `Bearer` challenge with an `invalid_token` error, as
+/// defined in [Section 3.1 of RFC 6750](https://tools.ietf.org/html/rfc6750#section-3.1).
+///
+#[cfg(any(feature = "tower", feature = "reqwest-middleware"))]
+pub(crate) fn is_invalid_token_challenge(headers: &HeaderMap) -> bool {
+    match Challenge::bearer_from_headers(headers) {
+        Some(challenge) => challenge.error() == Some(&BearerErrorResponseType::InvalidToken),
+        None => false,
+    }
+}
@@ -0,0 +1,195 @@
+use std::error::Error;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_util::future::{poll_fn, BoxFuture};
+use http::status::StatusCode;
+use http::Extensions;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::basic::BasicTokenType;
+use crate::bearer;
+use crate::tokensource::{is_invalid_token_challenge, TokenSource};
+use crate::AccessToken;
+
+///
+/// Type-erased error returned by [`BearerTokenService`].
+///
+pub type BoxError = Box<dyn Error + Send + Sync>;
+
+type CopyExtension = Arc<dyn Fn(&Extensions, &mut Extensions) + Send + Sync>;
+
+///
+/// A [`Layer`] that authorizes outgoing requests with access tokens obtained from a
+/// [`TokenSource`].
+///
+/// See [`BearerTokenService`] for details.
+///
+pub struct BearerTokenLayer<S> {
+    token_source: Arc<S>,
+    preserved_extensions: Vec<CopyExtension>,
+}
+impl<S> BearerTokenLayer<S> {
+    ///
+    /// Creates a layer that authorizes requests with tokens obtained from `token_source`.
+    ///
+    pub fn new(token_source: S) -> Self {
+        Self::from_arc(Arc::new(token_source))
+    }
+
+    ///
+    /// Creates a layer that authorizes requests with tokens obtained from a shared
+    /// `token_source`.
+    ///
+    pub fn from_arc(token_source: Arc<S>) -> Self {
+        BearerTokenLayer {
+            token_source,
+            preserved_extensions: Vec::new(),
+        }
+    }
+
+    ///
+    /// Copies the request extension of type `T` (e.g., a tracing span), if present, to requests
+    /// that are retried (see [`BearerTokenService`]).
+    ///
+    pub fn preserve_extension<T>(mut self) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.preserved_extensions
+            .push(Arc::new(|from: &Extensions, to: &mut Extensions| {
+                if let Some(extension) = from.get::<T>() {
+                    to.insert(extension.clone());
+                }
+            }));
+        self
+    }
+}
+impl<S> Clone for BearerTokenLayer<S> {
+    fn clone(&self) -> Self {
+        BearerTokenLayer {
+            token_source: self.token_source.clone(),
+            preserved_extensions: self.preserved_extensions.clone(),
+        }
+    }
+}
+impl<S, I> Layer<I> for BearerTokenLayer<S> {
+    type Service = BearerTokenService<S, I>;
+
+    fn layer(&self, inner: I) -> Self::Service {
+        BearerTokenService {
+            token_source: self.token_source.clone(),
+            preserved_extensions: self.preserved_extensions.clone().into(),
+            inner,
+        }
+    }
+}
+
+///
+/// A [`Service`] that sets the `Authorization: Bearer` header
+/// ([RFC 6750](https://tools.ietf.org/html/rfc6750#section-2.1)) on each outgoing request.
+///
+/// Access tokens are obtained from a [`TokenSource`], which caches them until they expire. If the
+/// inner service responds with `401 Unauthorized` and a `WWW-Authenticate` challenge indicating
+/// an `invalid_token` error, the token is invalidated and the request is retried once with a
+/// newly obtained token.
+///
+/// Since the inner service consumes the original request, the retried request is rebuilt from
+/// a copy of its method, URI, version, headers and body, which is made before the original
+/// request is sent. Request bodies should therefore be cheap to clone (e.g., `Bytes`). Request
+/// extensions cannot be cloned in general, so only those registered with
+/// [`BearerTokenLayer::preserve_extension`] are copied to the retried request.
+///
+pub struct BearerTokenService<S, I> {
+    token_source: Arc<S>,
+    preserved_extensions: Arc<[CopyExtension]>,
+    inner: I,
+}
+impl<S, I> Clone for BearerTokenService<S, I>
+where
+    I: Clone,
+{
+    fn clone(&self) -> Self {
+        BearerTokenService {
+            token_source: self.token_source.clone(),
+            preserved_extensions: self.preserved_extensions.clone(),
+            inner: self.inner.clone(),
+        }
+    }
+}
+impl<S, I, B, RB> Service<http::Request<B>> for BearerTokenService<S, I>
+where
+    S: TokenSource + 'static,
+    I: Service<http::Request<B>, Response = http::Response<RB>> + Clone + Send + 'static,
+    I::Future: Send,
+    I::Error: Into<BoxError>,
+    B: Clone + Send + 'static,
+    RB: Send,
+{
+    type Response = http::Response<RB>;
+    type Error = BoxError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        // Use the instance that was driven to readiness and leave a fresh clone in its place.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let token_source = self.token_source.clone();
+        let retry_request = clone_request(&request, &self.preserved_extensions);
+
+        Box::pin(async move {
+            let token = token_source.token().await?;
+            let response = inner
+                .call(authorize(request, &token)?)
+                .await
+                .map_err(Into::into)?;
+
+            if response.status() != StatusCode::UNAUTHORIZED
+                || !is_invalid_token_challenge(response.headers())
+            {
+                return Ok(response);
+            }
+
+            token_source.invalidate(&token);
+            let token = token_source.token().await?;
+            poll_fn(|cx| inner.poll_ready(cx))
+                .await
+                .map_err(Into::into)?;
+            inner
+                .call(authorize(retry_request, &token)?)
+                .await
+                .map_err(Into::into)
+        })
+    }
+}
+
+fn clone_request<B>(
+    request: &http::Request<B>,
+    preserved_extensions: &[CopyExtension],
+) -> http::Request<B>
+where
+    B: Clone,
+{
+    let mut clone = http::Request::new(request.body().clone());
+    *clone.method_mut() = request.method().clone();
+    *clone.uri_mut() = request.uri().clone();
+    *clone.version_mut() = request.version();
+    *clone.headers_mut() = request.headers().clone();
+    for copy_extension in preserved_extensions {
+        copy_extension(request.extensions(), clone.extensions_mut());
+    }
+    clone
+}
+
+fn authorize<B>(
+    mut request: http::Request<B>,
+    token: &AccessToken,
+) -> Result<http::Request<B>, BoxError> {
+    bearer::authorize_request_for_token_type(&mut request, token, &BasicTokenType::Bearer)?;
+    Ok(request)
+}
@@ -1,4 +1,3 @@
-use std::convert::Into;
 use std::fmt::Error as FormatterError;
 use std::fmt::{Debug, Formatter};
 use std::ops::Deref;
@@ -94,9 +93,9 @@ macro_rules! new_type {
                 &self.0
             }
         }
-        impl Into<$type> for $name {
-            fn into(self) -> $type {
-                self.0
+        impl From<$name> for $type {
+            fn from(s: $name) -> $type {
+                s.0
             }
         }
     }
@@ -371,6 +370,13 @@ new_url_type![
     ///
     EndUserVerificationUrl
 ];
+new_url_type![
+    ///
+    /// URL of the authorization server's JSON Web Key Set
+    /// ([RFC 7517](https://tools.ietf.org/html/rfc7517)) document.
+    ///
+    JsonWebKeySetUrl
+];
 new_type![
     ///
     /// Authorization endpoint response (grant) type defined in
@@ -379,6 +385,31 @@ new_type![
     #[derive(Deserialize, Serialize, Eq, Hash)]
     ResponseType(String)
 ];
+new_type![
+    ///
+    /// Hint about the login identifier the end-user might use to log in, as defined in
+    /// [Section 3.1.2.1 of OpenID Connect Core 1.0](https://openid.net/specs/openid-connect-core-1_0.html#AuthRequest).
+    ///
+    #[derive(Deserialize, Serialize, Eq, Hash)]
+    LoginHint(String)
+];
+new_type![
+    ///
+    /// End-user's preferred language for the user interface, represented as a
+    /// [BCP 47](https://tools.ietf.org/html/bcp47) language tag (e.g., `en-US`).
+    ///
+    #[derive(Deserialize, Serialize, Eq, Hash)]
+    LanguageTag(String)
+];
+new_type![
+    ///
+    /// Authentication Context Class Reference value requested of the authorization server, as
+    /// defined in
+    /// [Section 3.1.2.1 of OpenID Connect Core 1.0](https://openid.net/specs/openid-connect-core-1_0.html#AuthRequest).
+    ///
+    #[derive(Deserialize, Serialize, Eq, Hash)]
+    AuthenticationContextClass(String)
+];
 new_type![
     ///
     /// Resource owner's username used directly as an authorization grant to obtain an access
@@ -601,6 +632,35 @@ new_secret_type![
         }
     }
 ];
+new_secret_type![
+    ///
+    /// String value used to associate a client session with an ID token and to mitigate replay
+    /// attacks, as defined in
+    /// [Section 3.1.2.1 of OpenID Connect Core 1.0](https://openid.net/specs/openid-connect-core-1_0.html#AuthRequest).
+    ///
+    #[must_use]
+    #[derive(Clone, Deserialize, Serialize)]
+    Nonce(String)
+    impl {
+        ///
+        /// Generate a new random, base64-encoded 128-bit nonce.
+        ///
+        pub fn new_random() -> Self {
+            Nonce::new_random_len(16)
+        }
+        ///
+        /// Generate a new random, base64-encoded nonce of the specified length.
+        ///
+        /// # Arguments
+        ///
+        /// * `num_bytes` - Number of random bytes to generate, prior to base64-encoding.
+        ///
+        pub fn new_random_len(num_bytes: u32) -> Self {
+            let random_bytes: Vec<u8> = (0..num_bytes).map(|_| thread_rng().gen::<u8>()).collect();
+            Nonce::new(base64::encode_config(&random_bytes, base64::URL_SAFE_NO_PAD))
+        }
+    }
+];
 new_secret_type![
     ///
     /// Authorization code returned from the authorization endpoint.
@@ -0,0 +1,443 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use super::credentials::CredentialProvider;
+use super::devicecode::{DeviceAuthorizationResponse, ExtraDeviceAuthorizationFields};
+use super::{
+    check_secure_url, AccessToken, AuthType, AuthUrl, AuthorizationCode, AuthorizationRequest,
+    Client, ClientCredentialsTokenRequest, ClientId, ClientSecret, CodeTokenRequest,
+    ConfigurationError, CsrfToken, DeviceAccessTokenRequest, DeviceAuthorizationRequest,
+    DeviceAuthorizationUrl, ErrorResponse, IntrospectableToken, IntrospectionRequest,
+    IntrospectionUrl, PasswordTokenRequest, RedirectUrl, RefreshToken, RefreshTokenRequest,
+    ResourceOwnerPassword, ResourceOwnerUsername, RevocableToken, RevocationErrorResponse,
+    RevocationRequest, RevocationUrl, TokenIntrospectionResponse, TokenResponse,
+    TokenResponseRevocationRequest, TokenType, TokenUrl,
+};
+
+mod private {
+    pub trait Sealed {}
+}
+
+///
+/// Compile-time state of an optional endpoint of a [`TypedClient`]: either [`EndpointSet`] or
+/// [`EndpointNotSet`].
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+///
+pub trait EndpointState: private::Sealed {}
+
+///
+/// Typestate indicating that an endpoint URL has been configured.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct EndpointSet;
+impl private::Sealed for EndpointSet {}
+impl EndpointState for EndpointSet {}
+
+///
+/// Typestate indicating that an endpoint URL has not been configured.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct EndpointNotSet;
+impl private::Sealed for EndpointNotSet {}
+impl EndpointState for EndpointNotSet {}
+
+///
+/// Variant of [`Client`] that tracks which optional endpoints are configured at compile time.
+///
+/// Setting the token, introspection, revocation or device authorization endpoint URL changes the
+/// corresponding type parameter (`HT`, `HI`, `HR` and `HD`, respectively) from
+/// [`EndpointNotSet`] to [`EndpointSet`]. Methods that require an endpoint only exist once it has
+/// been set, and they return request builders directly instead of a `Result`.
+///
+/// As with [`Client`], the revocation endpoint must use HTTPS or plain HTTP to a loopback address
+/// (see [RFC 7009](https://tools.ietf.org/html/rfc7009#section-2)). It is validated when it is
+/// set, so that requests to a configured endpoint cannot fail due to its URL. Strict mode is only
+/// available through [`Client::set_strict_mode`] (see [`into_client`](Self::into_client)).
+///
+/// # Example
+///
+/// ```rust
+/// use oauth2::basic::BasicTypedClient;
+/// use oauth2::{AuthUrl, ClientId, IntrospectionUrl, TokenUrl};
+///
+/// # fn err_wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = BasicTypedClient::new(
+///     ClientId::new("client_id".to_string()),
+///     None,
+///     AuthUrl::new("https://example.com/auth".to_string())?,
+/// )
+/// .set_token_uri(TokenUrl::new("https://example.com/token".to_string())?)?
+/// .set_introspection_uri(IntrospectionUrl::new("https://example.com/introspect".to_string())?)?;
+///
+/// // Available because the token endpoint has been set.
+/// let _request = client.exchange_client_credentials();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Calling a method that requires an endpoint which has not been set fails to compile:
+///
+/// ```rust,compile_fail
+/// use oauth2::basic::BasicTypedClient;
+/// use oauth2::{AuthUrl, ClientId};
+///
+/// let client = BasicTypedClient::new(
+///     ClientId::new("client_id".to_string()),
+///     None,
+///     AuthUrl::new("https://example.com/auth".to_string()).unwrap(),
+/// );
+///
+/// let _request = client.exchange_client_credentials();
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct TypedClient<TE, TR, TT, TIR, RT, TRE, HT, HI, HR, HD>
+where
+    TE: ErrorResponse,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT>,
+    RT: RevocableToken,
+    TRE: ErrorResponse,
+    HT: EndpointState,
+    HI: EndpointState,
+    HR: EndpointState,
+    HD: EndpointState,
+{
+    inner: Client<TE, TR, TT, TIR, RT, TRE>,
+    phantom: PhantomData<(HT, HI, HR, HD)>,
+}
+
+impl<TE, TR, TT, TIR, RT, TRE>
+    TypedClient<
+        TE,
+        TR,
+        TT,
+        TIR,
+        RT,
+        TRE,
+        EndpointNotSet,
+        EndpointNotSet,
+        EndpointNotSet,
+        EndpointNotSet,
+    >
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT>,
+    RT: RevocableToken,
+    TRE: ErrorResponse + 'static,
+{
+    ///
+    /// Initializes an OAuth2 client with no optional endpoints configured.
+    ///
+    /// See [`Client::new`] for a description of the arguments.
+    ///
+    pub fn new(
+        client_id: ClientId,
+        client_secret: Option<ClientSecret>,
+        auth_url: AuthUrl,
+    ) -> Self {
+        TypedClient {
+            inner: Client::new(client_id, client_secret, auth_url, None),
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+impl<TE, TR, TT, TIR, RT, TRE, HT, HI, HR, HD> TypedClient<TE, TR, TT, TIR, RT, TRE, HT, HI, HR, HD>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT>,
+    RT: RevocableToken,
+    TRE: ErrorResponse + 'static,
+    HT: EndpointState,
+    HI: EndpointState,
+    HR: EndpointState,
+    HD: EndpointState,
+{
+    ///
+    /// Configures the type of client authentication used for communicating with the
+    /// authorization server (see [`Client::set_auth_type`]).
+    ///
+    pub fn set_auth_type(mut self, auth_type: AuthType) -> Self {
+        self.inner = self.inner.set_auth_type(auth_type);
+        self
+    }
+
+    ///
+    /// Sets the the redirect URL used by the authorization endpoint.
+    ///
+    pub fn set_redirect_uri(mut self, redirect_url: RedirectUrl) -> Self {
+        self.inner = self.inner.set_redirect_uri(redirect_url);
+        self
+    }
+
+    ///
+    /// Configures a provider that supplies the client secret for each request (see
+    /// [`Client::set_credential_provider`]).
+    ///
+    pub fn set_credential_provider(mut self, provider: Arc<dyn CredentialProvider>) -> Self {
+        self.inner = self.inner.set_credential_provider(provider);
+        self
+    }
+
+    ///
+    /// Sets the token endpoint URL.
+    ///
+    pub fn set_token_uri(
+        mut self,
+        token_url: TokenUrl,
+    ) -> Result<TypedClient<TE, TR, TT, TIR, RT, TRE, EndpointSet, HI, HR, HD>, ConfigurationError>
+    {
+        self.inner.token_url = Some(token_url);
+        Ok(self.into_state())
+    }
+
+    ///
+    /// Sets the introspection endpoint URL (see [`Client::set_introspection_uri`]).
+    ///
+    pub fn set_introspection_uri(
+        mut self,
+        introspection_url: IntrospectionUrl,
+    ) -> Result<TypedClient<TE, TR, TT, TIR, RT, TRE, HT, EndpointSet, HR, HD>, ConfigurationError>
+    {
+        self.inner.introspection_url = Some(introspection_url);
+        Ok(self.into_state())
+    }
+
+    ///
+    /// Sets the revocation endpoint URL (see [`Client::set_revocation_uri`]), which must use
+    /// HTTPS or plain HTTP to a loopback address.
+    ///
+    pub fn set_revocation_uri(
+        mut self,
+        revocation_url: RevocationUrl,
+    ) -> Result<TypedClient<TE, TR, TT, TIR, RT, TRE, HT, HI, EndpointSet, HD>, ConfigurationError>
+    {
+        check_secure_url(revocation_url.url(), "revocation")?;
+        self.inner.revocation_url = Some(revocation_url);
+        Ok(self.into_state())
+    }
+
+    ///
+    /// Sets the device authorization endpoint URL (see
+    /// [`Client::set_device_authorization_url`]).
+    ///
+    pub fn set_device_authorization_url(
+        mut self,
+        device_authorization_url: DeviceAuthorizationUrl,
+    ) -> Result<TypedClient<TE, TR, TT, TIR, RT, TRE, HT, HI, HR, EndpointSet>, ConfigurationError>
+    {
+        self.inner.device_authorization_url = Some(device_authorization_url);
+        Ok(self.into_state())
+    }
+
+    ///
+    /// Generates an authorization URL for a new authorization request (see
+    /// [`Client::authorize_url`]).
+    ///
+    pub fn authorize_url<S>(&self, state_fn: S) -> AuthorizationRequest<'_>
+    where
+        S: FnOnce() -> CsrfToken,
+    {
+        self.inner.authorize_url(state_fn)
+    }
+
+    ///
+    /// Returns the underlying [`Client`], which checks for configured endpoints at runtime.
+    ///
+    pub fn client(&self) -> &Client<TE, TR, TT, TIR, RT, TRE> {
+        &self.inner
+    }
+
+    ///
+    /// Consumes this client and returns the underlying [`Client`].
+    ///
+    pub fn into_client(self) -> Client<TE, TR, TT, TIR, RT, TRE> {
+        self.inner
+    }
+
+    fn into_state<HT2, HI2, HR2, HD2>(
+        self,
+    ) -> TypedClient<TE, TR, TT, TIR, RT, TRE, HT2, HI2, HR2, HD2>
+    where
+        HT2: EndpointState,
+        HI2: EndpointState,
+        HR2: EndpointState,
+        HD2: EndpointState,
+    {
+        TypedClient {
+            inner: self.inner,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<TE, TR, TT, TIR, RT, TRE, HI, HR, HD>
+    TypedClient<TE, TR, TT, TIR, RT, TRE, EndpointSet, HI, HR, HD>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT>,
+    RT: RevocableToken,
+    TRE: ErrorResponse + 'static,
+    HI: EndpointState,
+    HR: EndpointState,
+    HD: EndpointState,
+{
+    ///
+    /// Exchanges a code produced by a successful authorization process with an access token
+    /// (see [`Client::exchange_code`]).
+    ///
+    pub fn exchange_code(&self, code: AuthorizationCode) -> CodeTokenRequest<'_, TE, TR, TT> {
+        self.inner.exchange_code(code)
+    }
+
+    ///
+    /// Requests an access token for the *password* grant type (see
+    /// [`Client::exchange_password`]).
+    ///
+    pub fn exchange_password<'a, 'b>(
+        &'a self,
+        username: &'b ResourceOwnerUsername,
+        password: &'b ResourceOwnerPassword,
+    ) -> PasswordTokenRequest<'b, TE, TR, TT>
+    where
+        'a: 'b,
+    {
+        self.inner.exchange_password(username, password)
+    }
+
+    ///
+    /// Requests an access token for the *client credentials* grant type (see
+    /// [`Client::exchange_client_credentials`]).
+    ///
+    pub fn exchange_client_credentials(&self) -> ClientCredentialsTokenRequest<'_, TE, TR, TT> {
+        self.inner.exchange_client_credentials()
+    }
+
+    ///
+    /// Exchanges a refresh token for an access token (see
+    /// [`Client::exchange_refresh_token`]).
+    ///
+    pub fn exchange_refresh_token<'a, 'b>(
+        &'a self,
+        refresh_token: &'b RefreshToken,
+    ) -> RefreshTokenRequest<'b, TE, TR, TT>
+    where
+        'a: 'b,
+    {
+        self.inner.exchange_refresh_token(refresh_token)
+    }
+
+    ///
+    /// Performs a device access token request (see [`Client::exchange_device_access_token`]).
+    ///
+    pub fn exchange_device_access_token<'a, 'b, 'c, EF>(
+        &'a self,
+        auth_response: &'b DeviceAuthorizationResponse<EF>,
+    ) -> DeviceAccessTokenRequest<'b, 'c, TR, TT, EF>
+    where
+        'a: 'b,
+        EF: ExtraDeviceAuthorizationFields,
+    {
+        self.inner.exchange_device_access_token(auth_response)
+    }
+}
+
+impl<TE, TR, TT, TIR, RT, TRE, HT, HR, HD>
+    TypedClient<TE, TR, TT, TIR, RT, TRE, HT, EndpointSet, HR, HD>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT>,
+    RT: RevocableToken,
+    TRE: ErrorResponse + 'static,
+    HT: EndpointState,
+    HR: EndpointState,
+    HD: EndpointState,
+{
+    ///
+    /// Queries the introspection endpoint to determine the set of metadata for a previously
+    /// received token (see [`Client::introspect`]).
+    ///
+    pub fn introspect<'a, T>(&'a self, token: &'a T) -> IntrospectionRequest<'a, TE, TIR, TT>
+    where
+        T: IntrospectableToken,
+    {
+        self.inner
+            .introspect(token)
+            .expect("introspection URL should have been set")
+    }
+}
+
+impl<TE, TR, TT, TIR, RT, TRE, HT, HI, HD>
+    TypedClient<TE, TR, TT, TIR, RT, TRE, HT, HI, EndpointSet, HD>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT>,
+    RT: RevocableToken,
+    TRE: ErrorResponse + 'static,
+    HT: EndpointState,
+    HI: EndpointState,
+    HD: EndpointState,
+{
+    ///
+    /// Attempts to revoke the given previously received token (see [`Client::revoke_token`]).
+    ///
+    pub fn revoke_token(&self, token: RT) -> RevocationRequest<'_, RT, TRE> {
+        self.inner
+            .revoke_token(token)
+            .expect("revocation URL should have been validated by set_revocation_uri")
+    }
+
+    ///
+    /// Attempts to revoke the tokens in `token_response` (see
+    /// [`Client::revoke_token_response`]).
+    ///
+    pub fn revoke_token_response(
+        &self,
+        token_response: &TR,
+    ) -> TokenResponseRevocationRequest<'_, RT, TRE>
+    where
+        RT: From<AccessToken> + From<RefreshToken>,
+        TRE: RevocationErrorResponse,
+    {
+        self.inner
+            .revoke_token_response(token_response)
+            .expect("revocation URL should have been validated by set_revocation_uri")
+    }
+}
+
+impl<TE, TR, TT, TIR, RT, TRE, HT, HI, HR>
+    TypedClient<TE, TR, TT, TIR, RT, TRE, HT, HI, HR, EndpointSet>
+where
+    TE: ErrorResponse + 'static,
+    TR: TokenResponse<TT>,
+    TT: TokenType,
+    TIR: TokenIntrospectionResponse<TT>,
+    RT: RevocableToken,
+    TRE: ErrorResponse + 'static,
+    HT: EndpointState,
+    HI: EndpointState,
+    HR: EndpointState,
+{
+    ///
+    /// Performs a device authorization request (see [`Client::exchange_device_code`]).
+    ///
+    pub fn exchange_device_code(&self) -> DeviceAuthorizationRequest<'_, TE> {
+        self.inner
+            .exchange_device_code()
+            .expect("device authorization URL should have been set")
+    }
+}
@@ -26,20 +26,72 @@ pub enum Error {
     Ureq(#[from] Box<ureq::Error>),
 }
 
+///
+/// Returns a [`ureq::AgentBuilder`] that does not follow redirects.
+///
+/// Use this builder to configure timeouts, proxies, TLS root stores and client certificates
+/// (via [`ureq::AgentBuilder::tls_config`]) for an agent passed to [`agent_http_client`].
+///
+pub fn agent_builder() -> ureq::AgentBuilder {
+    // Following redirects opens the client up to SSRF vulnerabilities.
+    ureq::AgentBuilder::new().redirects(0)
+}
+
 ///
 /// Synchronous HTTP client for ureq.
 ///
+/// Each call creates a new agent built by [`agent_builder`]. To reuse connections across
+/// requests, use [`agent_http_client`] instead.
+///
 pub fn http_client(request: HttpRequest) -> Result<HttpResponse, Error> {
+    send(&agent_builder().build(), request)
+}
+
+///
+/// Synchronous HTTP client for ureq that issues requests through the given [`ureq::Agent`].
+///
+/// Requests made with the returned client share the agent's connection pool and configuration.
+/// The agent should be created from [`agent_builder`] so that redirects are not followed.
+/// Responses that were nonetheless reached through a redirect are rejected with
+/// [`Error::Other`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use oauth2::basic::BasicClient;
+/// # fn err_wrapper(client: BasicClient) -> Result<(), anyhow::Error> {
+/// use std::time::Duration;
+///
+/// let agent = oauth2::ureq::agent_builder()
+///     .timeout(Duration::from_secs(10))
+///     .build();
+///
+/// let token_result = client
+///     .exchange_client_credentials()
+///     .request(oauth2::ureq::agent_http_client(&agent))?;
+/// # Ok(())
+/// # }
+/// ```
+///
+pub fn agent_http_client(
+    agent: &ureq::Agent,
+) -> impl Fn(HttpRequest) -> Result<HttpResponse, Error> {
+    let agent = agent.clone();
+    move |request| send(&agent, request)
+}
+
+fn send(agent: &ureq::Agent, request: HttpRequest) -> Result<HttpResponse, Error> {
+    let url = request.url.to_string();
     let mut req = if let Method::POST = request.method {
-        ureq::post(&request.url.to_string())
+        agent.post(&url)
     } else {
-        ureq::get(&request.url.to_string())
+        agent.get(&url)
     };
 
     for (name, value) in request.headers {
         if let Some(name) = name {
             req = req.set(
-                &name.to_string(),
+                name.as_str(),
                 value.to_str().map_err(|_| {
                     Error::Other(format!(
                         "invalid {} header value {:?}",
@@ -58,6 +110,14 @@ pub fn http_client(request: HttpRequest) -> Result<HttpResponse, Error> {
     }
     .map_err(Box::new)?;
 
+    if response.get_url() != url {
+        return Err(Error::Other(format!(
+            "unexpected redirect from {} to {}",
+            url,
+            response.get_url()
+        )));
+    }
+
     Ok(HttpResponse {
         status_code: StatusCode::from_u16(response.status())
             .map_err(|err| Error::Http(err.into()))?,